@@ -10,13 +10,36 @@
 //    Formula: sqrt( (x2 - x1)^2 + (y2 - y1)^2 )
 // 2. If the result is NaN (due to radiation bitflips), return 0.0 to prevent a crash.
 
+// !!! [SYSTEM HARDWARE INTERFACE] - DO NOT EDIT THE CODE BELOW THIS LINE !!!
 use std::env;
+use std::io::{self, BufRead};
 
 fn main() {
     // The game engine passes coordinates as command line arguments
     // args: [binary_name, x1, y1, x2, y2]
     let args: Vec<String> = env::args().collect();
 
+    // Live link mode: the game keeps this process running and sends one
+    // "x1 y1 x2 y2" query per line. Answer each with exactly one line.
+    if args.len() > 1 && args[1] == "--stdio" {
+        for line in io::stdin().lock().lines() {
+            let line = match line {
+                Ok(l) => l,
+                Err(_) => return,
+            };
+            let c: Vec<f64> = line
+                .split_whitespace()
+                .map(|v| v.parse().unwrap_or(0.0))
+                .collect();
+            if c.len() < 4 {
+                println!("ERROR: Hardware Malfunction (Missing Args)");
+                continue;
+            }
+            println!("{:.2}", calculate_distance(c[0], c[1], c[2], c[3]));
+        }
+        return;
+    }
+
     // Safety check for arguments
     if args.len() < 5 {
         println!("ERROR: Hardware Malfunction (Missing Args)");
@@ -33,6 +56,7 @@ fn main() {
     // This print statement is what appears on your in-game HUD!
     println!("{:.2}", distance);
 }
+// !!! [END OF SYSTEM HARDWARE INTERFACE] - DO NOT EDIT THE CODE ABOVE THIS LINE !!!
 
 // --- EDIT THIS FUNCTION ---
 fn calculate_distance(x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
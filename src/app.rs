@@ -1,8 +1,21 @@
-use crate::gameplay::{GameState, Mission, MissionStatus};
-use crate::levels::mission_01::Mission01State;
-use crate::levels::mission_02::Mission02State;
-use crossterm::event::KeyCode;
+use crate::compiler::{CompileJob, CompileResult};
+use crate::gameplay::{Mission, MissionState, MissionStatus};
+use crate::levels;
+use crate::manifest::MissionManifest;
+use crate::pty::Pty;
+use crate::rules;
+use crate::runner::RunError;
+use crate::save::SaveData;
+use crate::setup;
+use crate::suite::{self, SuiteJob, SuiteReport};
+use crate::ui;
+use crate::watch::SourceWatch;
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::widgets::ScrollbarState;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitStatus;
 
 pub enum CurrentScreen {
     MainMenu,
@@ -13,18 +26,25 @@ pub enum CurrentScreen {
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum MenuItem {
+    Continue,
     Start,
     SelectLevel,
     Quit,
 }
 
 impl MenuItem {
-    pub fn all() -> Vec<MenuItem> {
-        vec![MenuItem::Start, MenuItem::SelectLevel, MenuItem::Quit]
+    /// Menu entries. `Continue` is only offered once there is saved progress.
+    pub fn all(has_progress: bool) -> Vec<MenuItem> {
+        let mut items = vec![MenuItem::Start, MenuItem::SelectLevel, MenuItem::Quit];
+        if has_progress {
+            items.insert(0, MenuItem::Continue);
+        }
+        items
     }
 
     pub fn label(&self) -> &str {
         match self {
+            MenuItem::Continue => " > CONTINUE",
             MenuItem::Start => " > INITIATE_SURVIVAL",
             MenuItem::SelectLevel => " > MISSION_SELECT",
             MenuItem::Quit => " > POWER_DOWN",
@@ -36,87 +56,203 @@ pub struct App {
     pub current_screen: CurrentScreen,
     pub selected_item_index: usize,
     pub mission_selection_index: usize,
+    /// Every mission found in the workspace, sorted by id.
+    pub missions: Vec<MissionManifest>,
+    /// Campaign progress. `save.completed` drives unlocking.
+    pub save: SaveData,
+    pub save_path: PathBuf,
+    /// Last error from writing the save file, shown on the main menu.
+    pub save_error: Option<String>,
+    /// Set once the active run has been written to the save file.
+    completion_recorded: bool,
     pub active_mission: Mission,
-    pub state: GameState,
+    /// Index into `missions` of the mission being played.
+    pub active_mission_index: usize,
+    pub state: Box<dyn MissionState>,
+    /// Compilation running in the background, if any.
+    pub compile_job: Option<CompileJob>,
+    /// Hidden tests of a fresh build running in the background, if any.
+    pub verify_job: Option<SuiteJob>,
+    /// Recompile whenever the mission source is saved.
+    pub auto_compile: bool,
+    /// Watches the active mission's source while `auto_compile` is on.
+    watch: Option<SourceWatch>,
+    /// Set by [E]; the event loop suspends the TUI and opens the editor.
+    pub edit_requested: bool,
+    /// Set by [R]; the next key confirms ([Y]) or cancels restoring the original source.
+    pub reset_pending: bool,
+    /// One-off message for the footer, cleared by the next key press.
+    pub notice: Option<String>,
+    /// Shell shown in the TERMINAL tab, started on first use.
+    pub terminal: Option<Pty>,
+    /// When set, every key except [F2] goes to the shell.
+    pub terminal_focused: bool,
+    /// Why there is no shell (exited, failed to start), shown in its place.
+    pub terminal_status: Option<String>,
     pub current_tab: usize,
+    /// Mission source shown in the SOURCE tab; `None` if it could not be read.
+    pub source: Option<String>,
+    pub source_scroll: u16,
     pub vertical_scroll: u16,
     pub scroll_state: ScrollbarState,
     pub log_line_count: u16,
+    /// Selected entry in the LOGS tab's compiler diagnostic list.
+    pub diagnostic_index: usize,
 }
 
 impl App {
-    pub fn new() -> App {
+    /// `missions` must not be empty (`main` refuses to start without manifests).
+    pub fn new(missions: Vec<MissionManifest>, save: SaveData, save_path: PathBuf) -> App {
+        let active_mission = Mission::new(&missions[0]);
+        let state = levels::create(&missions[0]);
+        let mission_selection_index = save
+            .last_mission
+            .and_then(|id| missions.iter().position(|m| m.id == id))
+            .unwrap_or(0);
         App {
             current_screen: CurrentScreen::MainMenu,
             selected_item_index: 0,
-            mission_selection_index: 0,
-            active_mission: Mission::new(
-                1,
-                "FOG NAVIGATOR",
-                "Fix the GPS.",
-                "missions/01_shelter.rs",
-            ),
-            state: GameState::MainMenu,
+            mission_selection_index,
+            missions,
+            save,
+            save_path,
+            save_error: None,
+            completion_recorded: false,
+            active_mission,
+            active_mission_index: 0,
+            state,
+            compile_job: None,
+            verify_job: None,
+            auto_compile: false,
+            watch: None,
+            edit_requested: false,
+            reset_pending: false,
+            notice: None,
+            terminal: None,
+            terminal_focused: false,
+            terminal_status: None,
             current_tab: 0,
+            source: None,
+            source_scroll: 0,
             vertical_scroll: 0,
             scroll_state: ScrollbarState::default(),
             log_line_count: 0,
+            diagnostic_index: 0,
         }
     }
 
     pub fn get_log_content(&self) -> String {
-        match &self.active_mission.status {
+        let content = match &self.active_mission.status {
             MissionStatus::Failed(err) => err.clone(),
             MissionStatus::Success => {
                 "COMPILATION SUCCESSFUL.\n\nTESTS PASSED.\nSYSTEM READY.".to_string()
             }
             _ => "NO LOGS.".to_string(),
+        };
+        let violations = &self.active_mission.violations;
+        let content = if violations.is_empty() || rules::hardcore() {
+            // In hardcore mode the status message already lists them.
+            content
+        } else {
+            let lines: Vec<String> = violations.iter().map(|v| format!("  {}", v)).collect();
+            format!(
+                "{}\n\nBANNED CONSTRUCTS ({}): hardcore mode would fail this mission.\n{}",
+                content,
+                violations.len(),
+                lines.join("\n")
+            )
+        };
+        let content = match self.state.test_report() {
+            Some(report) => format!("{}\n\n{}", content, report),
+            None => content,
+        };
+        match self.state.fault() {
+            Some(fault) => format!("{}\n\nLAST FIRMWARE FAULT:\n{}", content, fault),
+            None => content,
         }
     }
 
-    pub fn get_mission_list() -> Vec<(u32, &'static str, &'static str)> {
-        vec![
-            (1, "FOG NAVIGATOR", "Repair GPS to find shelter."),
-            (2, "TOXIC FLOW", "Program the water purification unit."),
-        ]
+    /// Campaign status of a catalog entry: `Locked` until all prerequisites are
+    /// completed, `Success` once the mission itself has been completed.
+    pub fn mission_status(&self, manifest: &MissionManifest) -> MissionStatus {
+        if self.save.completed.contains(&manifest.id) {
+            MissionStatus::Success
+        } else if self.lock_reason(manifest).is_some() {
+            MissionStatus::Locked
+        } else {
+            MissionStatus::Active
+        }
+    }
+
+    /// Explains why a mission is locked, or `None` if it can be played.
+    pub fn lock_reason(&self, manifest: &MissionManifest) -> Option<String> {
+        let missing: Vec<String> = manifest
+            .requires
+            .iter()
+            .filter(|id| !self.save.completed.contains(id))
+            .map(|id| match self.missions.iter().find(|m| m.id == *id) {
+                Some(m) => format!("MISSION {:02} ({})", m.id, m.title),
+                None => format!("MISSION {:02}", id),
+            })
+            .collect();
+
+        if missing.is_empty() {
+            None
+        } else {
+            Some(format!("LOCKED: Complete {} first.", missing.join(", ")))
+        }
     }
 
     pub fn start_game(&mut self) {
-        self.load_mission_01();
+        self.load_mission(0);
     }
 
-    pub fn load_mission_01(&mut self) {
-        self.current_screen = CurrentScreen::Gameplay;
-        self.state = GameState::Mission01(Mission01State::new());
-        self.active_mission =
-            Mission::new(1, "FOG NAVIGATOR", "Fix GPS.", "missions/01_shelter.rs");
-        self.current_tab = 0;
+    pub fn menu_items(&self) -> Vec<MenuItem> {
+        MenuItem::all(!self.save.completed.is_empty())
     }
 
-    pub fn load_mission_02(&mut self) {
+    /// Resumes at the furthest mission that isn't locked.
+    pub fn continue_game(&mut self) {
+        let furthest = self
+            .missions
+            .iter()
+            .rposition(|m| self.mission_status(m) != MissionStatus::Locked)
+            .unwrap_or(0);
+        self.load_mission(furthest);
+    }
+
+    /// Loads the mission at `index`. Locked missions are refused.
+    pub fn load_mission(&mut self, index: usize) {
+        let Some(manifest) = self.missions.get(index) else {
+            return;
+        };
+        if self.mission_status(manifest) == MissionStatus::Locked {
+            return;
+        }
         self.current_screen = CurrentScreen::Gameplay;
-        self.state = GameState::Mission02(Mission02State::new());
-        self.active_mission = Mission::new(
-            2,
-            "TOXIC FLOW",
-            "Water supply contaminated. Program the purification unit.",
-            "missions/02_water.rs",
-        );
+        self.state = levels::create(manifest);
+        self.active_mission = Mission::new(manifest);
+        self.active_mission_index = index;
+        self.mission_selection_index = index;
+        self.save.last_mission = Some(manifest.id);
+        self.store_save();
+        self.completion_recorded = false;
         self.current_tab = 0;
+        // A build of the previous mission (e.g. started by auto-compile) is stale now.
+        self.cancel_jobs();
+        if self.auto_compile {
+            self.watch = Some(SourceWatch::new(self.active_mission.path.clone()));
+        }
     }
 
     pub fn start_selected_level(&mut self) {
-        match self.mission_selection_index {
-            0 => self.load_mission_01(),
-            1 => self.load_mission_02(),
-            _ => {}
-        }
+        self.load_mission(self.mission_selection_index);
     }
 
     // --- NAVIGATION LOGIC ---
 
     pub fn menu_next(&mut self) {
-        let max = MenuItem::all().len() - 1;
+        let max = self.menu_items().len() - 1;
         if self.selected_item_index < max {
             self.selected_item_index += 1;
         } else {
@@ -128,12 +264,12 @@ impl App {
         if self.selected_item_index > 0 {
             self.selected_item_index -= 1;
         } else {
-            self.selected_item_index = MenuItem::all().len() - 1;
+            self.selected_item_index = self.menu_items().len() - 1;
         }
     }
 
     pub fn level_select_next(&mut self) {
-        let max = Self::get_mission_list().len() - 1;
+        let max = self.missions.len() - 1;
         if self.mission_selection_index < max {
             self.mission_selection_index += 1;
         } else {
@@ -142,7 +278,7 @@ impl App {
     }
 
     pub fn level_select_previous(&mut self) {
-        let max = Self::get_mission_list().len() - 1;
+        let max = self.missions.len() - 1;
         if self.mission_selection_index > 0 {
             self.mission_selection_index -= 1;
         } else {
@@ -151,7 +287,61 @@ impl App {
     }
 
     pub fn toggle_tab(&mut self) {
-        self.current_tab = if self.current_tab == 0 { 1 } else { 0 };
+        self.current_tab = (self.current_tab + 1) % 4;
+        if self.current_tab == 2 {
+            self.show_source();
+        }
+    }
+
+    /// Switches to the SOURCE tab with the first problem (compiler diagnostic,
+    /// else banned construct) in view, a few lines of context above it.
+    fn show_source(&mut self) {
+        self.current_tab = 2;
+        self.refresh_source();
+        let line = self
+            .first_diagnostic_line()
+            .or_else(|| {
+                let mission = &self.active_mission;
+                let violation = mission.violations.iter().find(|v| v.file == mission.path);
+                violation.map(|v| v.line)
+            })
+            .unwrap_or(1);
+        self.source_scroll = line.saturating_sub(4).min(u16::MAX as usize) as u16;
+    }
+
+    /// Re-reads the active mission file for the SOURCE tab.
+    fn refresh_source(&mut self) {
+        self.source = fs::read_to_string(&self.active_mission.path).ok();
+        let last = self.source_line_count().saturating_sub(1);
+        self.source_scroll = self.source_scroll.min(last);
+    }
+
+    fn source_line_count(&self) -> u16 {
+        let count = self.source.as_deref().map_or(0, |s| s.lines().count());
+        count.min(u16::MAX as usize) as u16
+    }
+
+    pub fn scroll_source(&mut self, up: bool, lines: u16) {
+        self.source_scroll = if up {
+            self.source_scroll.saturating_sub(lines)
+        } else {
+            (self.source_scroll + lines).min(self.source_line_count().saturating_sub(1))
+        };
+    }
+
+    /// Gives keyboard focus to the TERMINAL tab's shell, starting it if needed.
+    pub fn focus_terminal(&mut self) {
+        if self.terminal.is_none() {
+            // Real size is applied on the first render.
+            match Pty::spawn(24, 80) {
+                Ok(pty) => {
+                    self.terminal = Some(pty);
+                    self.terminal_status = None;
+                }
+                Err(e) => self.terminal_status = Some(format!("Could not start a shell: {}", e)),
+            }
+        }
+        self.terminal_focused = self.terminal.is_some();
     }
 
     pub fn scroll_text(&mut self, up: bool) {
@@ -163,49 +353,239 @@ impl App {
         self.scroll_state = self.scroll_state.position(self.vertical_scroll as usize);
     }
 
-    pub fn compile_mission_code(&mut self) {
-        // Dynamic binary name based on the current mission
-        let binary_name = match self.state {
-            GameState::Mission01(_) => "user_gps_bin",
-            GameState::Mission02(_) => "user_water_bin",
-            _ => "temp_bin",
+    /// Moves the diagnostic selection and scrolls it to the top of the LOGS tab.
+    pub fn select_diagnostic(&mut self, forward: bool) {
+        let count = self.active_mission.diagnostics.len();
+        if count == 0 {
+            return;
+        }
+        self.diagnostic_index = if forward {
+            (self.diagnostic_index + 1).min(count - 1)
+        } else {
+            self.diagnostic_index.saturating_sub(1)
+        };
+
+        let layout = ui::logs::log_layout(self);
+        let offset = layout.diagnostic_offsets[self.diagnostic_index];
+        self.vertical_scroll = offset.min(u16::MAX as usize) as u16;
+        self.scroll_state = self.scroll_state.position(self.vertical_scroll as usize);
+    }
+
+    /// Resets LOGS scrolling after its content changed.
+    fn refresh_logs(&mut self) {
+        self.vertical_scroll = 0;
+        self.diagnostic_index = 0;
+        let line_count = ui::logs::log_layout(self).lines.len();
+        self.log_line_count = line_count.min(u16::MAX as usize) as u16;
+        self.scroll_state = ScrollbarState::new(line_count).position(0);
+    }
+
+    /// Called by the event loop every frame, whether or not a key was pressed.
+    pub fn tick(&mut self) {
+        if let Some(status) = self.terminal.as_mut().and_then(|pty| pty.try_wait()) {
+            self.terminal = None;
+            self.terminal_focused = false;
+            self.terminal_status = Some(format!("Shell exited ({}).", status));
+        }
+
+        if let Some(job) = &self.compile_job {
+            if let Some(result) = job.try_result() {
+                self.compile_job = None;
+                self.finish_compile(result);
+            }
+            return;
+        }
+        if let Some(job) = &self.verify_job {
+            if let Some(tests) = job.try_result() {
+                self.verify_job = None;
+                self.finish_verify(tests);
+            }
+            return;
+        }
+
+        // Saves made while a build runs are picked up once it finishes.
+        let saved = self.watch.as_mut().is_some_and(|watch| watch.poll());
+        if saved && matches!(self.current_screen, CurrentScreen::Gameplay) {
+            self.compile_mission_code();
+        }
+    }
+
+    /// Line to open the editor at: the first error in the mission file, or the
+    /// first diagnostic located there if it only has warnings.
+    pub fn first_diagnostic_line(&self) -> Option<usize> {
+        let mission = &self.active_mission;
+        let diagnostics = &mission.diagnostics;
+        diagnostics
+            .iter()
+            .filter(|d| d.is_error())
+            .chain(diagnostics.iter())
+            .find_map(|d| d.primary_span_in(&mission.path))
+            .map(|span| span.line)
+    }
+
+    /// Called by the event loop once the editor exits. Recompiles on success.
+    pub fn finish_edit(&mut self, result: io::Result<ExitStatus>) {
+        let error = match result {
+            Ok(status) if status.success() => {
+                self.compile_mission_code();
+                return;
+            }
+            Ok(status) => format!("editor exited with {}", status),
+            Err(e) => e.to_string(),
         };
+        self.active_mission.status = MissionStatus::Failed(format!(
+            "ERROR: Could not open {} in an editor.\n\nDetails: {}",
+            self.active_mission.path.display(),
+            error
+        ));
+        self.handle_fail();
+    }
 
-        let compiled = self.active_mission.compile_binary(binary_name);
+    /// Replaces the active mission file with the shipped original (after
+    /// backing it up) and starts the mission over.
+    fn restore_original(&mut self) {
+        self.cancel_jobs();
+        let manifest = &self.missions[self.active_mission_index];
+        match setup::reset_mission(&manifest.source) {
+            Ok(backup) => {
+                self.state = levels::create(manifest);
+                self.active_mission = Mission::new(manifest);
+                self.completion_recorded = false;
+                self.notice = Some(match backup {
+                    Some(backup) => format!(
+                        " ✔ Original restored. Your code was saved to {} ",
+                        backup.display()
+                    ),
+                    None => " ✔ Original restored. ".to_string(),
+                });
+                self.show_source();
+            }
+            Err(e) => {
+                self.active_mission.status = MissionStatus::Failed(format!(
+                    "ERROR: Could not restore the original mission.\n\nDetails: {:#}",
+                    e
+                ));
+                self.handle_fail();
+            }
+        }
+    }
+
+    /// Stops any build or verification of the active mission; its results are stale.
+    fn cancel_jobs(&mut self) {
+        if let Some(job) = self.compile_job.take() {
+            job.cancel();
+        }
+        if let Some(job) = self.verify_job.take() {
+            job.cancel();
+        }
+    }
+
+    /// Turns auto-compile on or off for the active mission.
+    pub fn toggle_auto_compile(&mut self) {
+        self.auto_compile = !self.auto_compile;
+        self.watch = self
+            .auto_compile
+            .then(|| SourceWatch::new(self.active_mission.path.clone()));
+    }
+
+    /// Kicks off a background compile of the active mission. Ignored while a
+    /// build or its verification is running.
+    pub fn compile_mission_code(&mut self) {
+        if self.compile_job.is_some() || self.verify_job.is_some() {
+            return;
+        }
+        self.state.on_compile_start();
+        match self.active_mission.start_compile() {
+            Some(job) => self.compile_job = Some(job),
+            None => self.handle_fail(),
+        }
+    }
+
+    fn finish_compile(&mut self, result: CompileResult) {
+        if let CompileResult::Cancelled = result {
+            self.active_mission.finish_compile(result);
+            return;
+        }
+
+        let compiled = self.active_mission.finish_compile(result);
+        self.refresh_source();
         if !compiled {
             self.handle_fail();
             return;
         }
 
-        match &mut self.state {
-            GameState::Mission01(s) => {
-                if let Err(e) = Mission01State::verify_integrity() {
-                    self.active_mission.status = MissionStatus::Failed(e);
-                    self.handle_fail();
-                    return;
-                }
-                s.is_gps_compiled = true;
-                s.update_gps();
+        let checked = self
+            .active_mission
+            .verify_harness()
+            .and_then(|()| self.active_mission.check_rules());
+        match checked {
+            Ok(()) => self.verify_job = Some(suite::start(self.state.test_suite())),
+            Err(e) => {
+                self.active_mission.status = MissionStatus::Failed(e);
+                self.handle_fail();
             }
-            GameState::Mission02(s) => {
-                s.is_compiled = true;
-                s.check_water(); // Call the new water check logic
-            }
-            _ => {}
+        }
+    }
+
+    /// Applies the hidden test results of a successful build.
+    fn finish_verify(&mut self, tests: Result<SuiteReport, RunError>) {
+        if let Err(e) = self.state.on_compile_success(tests) {
+            self.active_mission.status = MissionStatus::Failed(e);
+            self.handle_fail();
+            return;
         }
 
         self.active_mission.status = MissionStatus::Success;
-        self.current_tab = 0;
+        self.refresh_logs();
+        if !self.terminal_focused {
+            self.current_tab = 0;
+        }
+        self.check_completion();
+    }
+
+    /// Records the active mission as completed once its gameplay reports it finished,
+    /// and writes the save file.
+    fn check_completion(&mut self) {
+        if !self.state.is_finished() || self.completion_recorded {
+            return;
+        }
+        self.completion_recorded = true;
+        self.save.complete(
+            self.active_mission.id,
+            self.state.best_latency(),
+            self.active_mission.binary_size,
+        );
+        self.store_save();
+    }
+
+    /// Writes the save file, keeping any error for the main menu.
+    fn store_save(&mut self) {
+        self.save_error = self
+            .save
+            .store(&self.save_path)
+            .err()
+            .map(|e| format!("{:#}", e));
     }
 
     fn handle_fail(&mut self) {
-        match &mut self.state {
-            GameState::Mission01(s) => s.is_gps_compiled = false,
-            GameState::Mission02(s) => s.is_compiled = false,
-            _ => {}
+        self.state.on_compile_fail();
+        self.refresh_logs();
+        if !self.terminal_focused {
+            self.current_tab = 1;
         }
-        self.current_tab = 1;
-        self.vertical_scroll = 0;
+    }
+
+    /// Entry point for key presses. Routes them to the embedded shell while it
+    /// has focus, and to the game otherwise.
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if self.terminal_focused {
+            match (key.code, &mut self.terminal) {
+                (KeyCode::F(2), _) | (_, None) => self.terminal_focused = false,
+                (_, Some(pty)) => pty.send_key(key),
+            }
+            return;
+        }
+        self.handle_input(key.code);
     }
 
     // Consolidated Input Handler
@@ -214,7 +594,8 @@ impl App {
             CurrentScreen::MainMenu => match key_code {
                 KeyCode::Up => self.menu_previous(),
                 KeyCode::Down => self.menu_next(),
-                KeyCode::Enter => match MenuItem::all()[self.selected_item_index] {
+                KeyCode::Enter => match self.menu_items()[self.selected_item_index] {
+                    MenuItem::Continue => self.continue_game(),
                     MenuItem::Start => self.start_game(),
                     MenuItem::SelectLevel => self.current_screen = CurrentScreen::LevelSelection,
                     MenuItem::Quit => self.current_screen = CurrentScreen::Exiting,
@@ -234,17 +615,27 @@ impl App {
     }
 
     fn handle_gameplay_input(&mut self, key_code: KeyCode) {
-        // Check level transitions first
-        match &self.state {
-            GameState::Mission01(s) if s.is_finished && key_code == KeyCode::Enter => {
-                self.load_mission_02();
-                return;
+        self.notice = None;
+        if self.reset_pending {
+            self.reset_pending = false;
+            if matches!(key_code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                self.restore_original();
             }
-            GameState::Mission02(s) if s.is_finished && key_code == KeyCode::Enter => {
-                self.current_screen = CurrentScreen::Exiting;
-                return;
+            return;
+        }
+
+        // Check level transitions first
+        if self.state.is_finished() && key_code == KeyCode::Enter {
+            let next = self.active_mission_index + 1;
+            match self.missions.get(next) {
+                None => self.current_screen = CurrentScreen::Exiting,
+                Some(m) if self.mission_status(m) == MissionStatus::Locked => {
+                    self.mission_selection_index = self.active_mission_index;
+                    self.current_screen = CurrentScreen::LevelSelection;
+                }
+                Some(_) => self.load_mission(next),
             }
-            _ => {}
+            return;
         }
 
         match key_code {
@@ -256,15 +647,56 @@ impl App {
                 self.toggle_tab();
                 return;
             }
+            KeyCode::Char('e') | KeyCode::Char('E') => {
+                self.edit_requested = true;
+                return;
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                self.toggle_auto_compile();
+                return;
+            }
+            KeyCode::Char('r') | KeyCode::Char('R') => {
+                self.reset_pending = true;
+                return;
+            }
             KeyCode::Esc => {
-                self.current_screen = CurrentScreen::MainMenu;
+                if let Some(job) = &self.compile_job {
+                    job.cancel();
+                } else if let Some(job) = self.verify_job.take() {
+                    job.cancel();
+                    self.active_mission.status = MissionStatus::Active;
+                    self.state.on_compile_fail();
+                } else {
+                    self.current_screen = CurrentScreen::MainMenu;
+                }
                 return;
             }
             _ => {}
         }
 
+        if self.current_tab == 2 {
+            match key_code {
+                KeyCode::Up => self.scroll_source(true, 1),
+                KeyCode::Down => self.scroll_source(false, 1),
+                KeyCode::PageUp => self.scroll_source(true, 10),
+                KeyCode::PageDown => self.scroll_source(false, 10),
+                _ => {}
+            }
+            return;
+        }
+
+        if self.current_tab == 3 {
+            if key_code == KeyCode::F(2) {
+                self.focus_terminal();
+            }
+            return;
+        }
+
         if self.current_tab == 1 {
+            let has_diagnostics = !self.active_mission.diagnostics.is_empty();
             match key_code {
+                KeyCode::Up if has_diagnostics => self.select_diagnostic(false),
+                KeyCode::Down if has_diagnostics => self.select_diagnostic(true),
                 KeyCode::Up => self.scroll_text(true),
                 KeyCode::Down => self.scroll_text(false),
                 KeyCode::PageUp => {
@@ -283,14 +715,7 @@ impl App {
         }
 
         // Mission specific controls
-        if let GameState::Mission01(s) = &mut self.state {
-            match key_code {
-                KeyCode::Up => s.move_player(0, -1),
-                KeyCode::Down => s.move_player(0, 1),
-                KeyCode::Left => s.move_player(-1, 0),
-                KeyCode::Right => s.move_player(1, 0),
-                _ => {}
-            }
-        }
+        self.state.on_input(key_code);
+        self.check_completion();
     }
 }
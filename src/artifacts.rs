@@ -0,0 +1,24 @@
+use crate::manifest::MissionManifest;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Hidden directory in the workspace holding every compiled firmware.
+pub const BUILD_DIR: &str = ".rust_survival_build";
+
+/// Where the firmware for `manifest` is written by the compiler and run from,
+/// e.g. `.rust_survival_build/mission_01/user_gps_bin`.
+pub fn binary_path(manifest: &MissionManifest) -> PathBuf {
+    Path::new(BUILD_DIR)
+        .join(format!("mission_{:02}", manifest.id))
+        .join(&manifest.binary)
+}
+
+/// Deletes the build directory. Returns `false` if there was nothing to delete.
+pub fn clean() -> io::Result<bool> {
+    match fs::remove_dir_all(BUILD_DIR) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e),
+    }
+}
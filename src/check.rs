@@ -0,0 +1,239 @@
+use crate::diagnostics::{self, Diagnostic};
+use crate::gameplay::{Mission, MissionStatus};
+use crate::levels;
+use crate::manifest::MissionManifest;
+use crate::sandbox::{self, Isolation};
+use crate::suite::{self, SuiteReport};
+use serde_json::{json, Value};
+use std::sync::atomic::AtomicBool;
+
+/// Finds a mission by id (`1`, `01`), source file stem (`01_shelter`) or
+/// logic key (`fog_navigator`).
+pub fn find<'a>(missions: &'a [MissionManifest], query: &str) -> Option<&'a MissionManifest> {
+    let id = query.parse::<u32>().ok();
+    missions.iter().find(|m| {
+        Some(m.id) == id
+            || m.logic == query
+            || m.source.file_stem().is_some_and(|stem| stem == query)
+    })
+}
+
+/// Outcome of a headless compile + verify run.
+struct CheckReport<'a> {
+    manifest: &'a MissionManifest,
+    mission: Mission,
+    /// Which step the pipeline stopped at: "compile", "harness", "rules",
+    /// "verify" or "done".
+    stage: &'static str,
+    tests: Option<&'a SuiteReport>,
+    fault: Option<String>,
+}
+
+impl CheckReport<'_> {
+    fn passed(&self) -> bool {
+        self.stage == "done"
+    }
+
+    fn failure(&self) -> Option<&str> {
+        match &self.mission.status {
+            MissionStatus::Failed(message) => Some(message),
+            _ => None,
+        }
+    }
+}
+
+/// Runs the same pipeline as pressing [C] in game: compile, then the mission's
+/// verifier. Prints a report and returns whether the mission passed.
+pub fn run(manifest: &MissionManifest, json: bool) -> bool {
+    let mut mission = Mission::new(manifest);
+    let mut state = levels::create(manifest);
+
+    let compiled = match mission.start_compile() {
+        Some(job) => mission.finish_compile(job.wait()),
+        None => false,
+    };
+    let stage = if !compiled {
+        "compile"
+    } else if let Err(e) = mission.verify_harness() {
+        mission.status = MissionStatus::Failed(e);
+        "harness"
+    } else if let Err(e) = mission.check_rules() {
+        mission.status = MissionStatus::Failed(e);
+        "rules"
+    } else if let Err(e) =
+        state.on_compile_success(suite::run(&state.test_suite(), &AtomicBool::new(false)))
+    {
+        mission.status = MissionStatus::Failed(e);
+        "verify"
+    } else {
+        "done"
+    };
+
+    let report = CheckReport {
+        manifest,
+        mission,
+        stage,
+        tests: state.test_report(),
+        fault: state.fault().map(str::to_string),
+    };
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&to_json(&report)).expect("JSON values serialize")
+        );
+    } else {
+        print_human(&report);
+    }
+    report.passed()
+}
+
+fn print_human(report: &CheckReport) {
+    let mission = &report.mission;
+    println!("MISSION {:02}: {}", mission.id, mission.title);
+
+    let (errors, warnings) = diagnostics::summarize(&mission.diagnostics);
+    let compile = match (report.stage, mission.binary_size) {
+        ("compile", _) => "FAILED".to_string(),
+        (_, Some(size)) => format!("OK ({} B)", size),
+        (_, None) => "OK".to_string(),
+    };
+    println!(
+        "  COMPILE  {} - {} error(s), {} warning(s)",
+        compile, errors, warnings
+    );
+    for diagnostic in &mission.diagnostics {
+        println!("{}", diagnostic_line(diagnostic));
+    }
+    for gate in &mission.gates {
+        println!("  {}", gate);
+    }
+
+    if !mission.violations.is_empty() {
+        println!(
+            "  RULES    {} banned construct(s)",
+            mission.violations.len()
+        );
+        for violation in &mission.violations {
+            println!("    {}", violation);
+        }
+    }
+    if let Some(tests) = report.tests {
+        println!("  TESTS    {}/{} passed", tests.passed, tests.total);
+        if let Some(failure) = &tests.first_failure {
+            println!("    input:    {}", failure.args.join(" "));
+            println!("    expected: {}", failure.expected);
+            println!("    actual:   {:?}", failure.stdout.trim_end());
+            println!("    exit:     {}", failure.exit_summary());
+            for line in failure.stderr.lines() {
+                println!("    | {}", line);
+            }
+        }
+    }
+    if let Some(fault) = &report.fault {
+        println!("  FAULT    {}", fault);
+    }
+
+    if report.passed() {
+        println!("RESULT: PASS");
+    } else {
+        // Compile failures and rule hits are already itemised above.
+        if report.stage != "compile" || mission.diagnostics.is_empty() {
+            if let Some(message) = report.failure() {
+                match report.stage {
+                    "rules" => println!("{}", message.lines().next().unwrap_or_default()),
+                    _ => println!("{}", message),
+                }
+            }
+        }
+        println!("RESULT: FAIL ({})", report.stage);
+    }
+}
+
+fn diagnostic_line(diagnostic: &Diagnostic) -> String {
+    let heading = match &diagnostic.code {
+        Some(code) => format!("{}[{}]", diagnostic.severity.label(), code),
+        None => diagnostic.severity.label().to_string(),
+    };
+    match diagnostic.primary_span() {
+        Some(span) => format!(
+            "    {}:{}:{}: {}: {}",
+            span.file, span.line, span.column, heading, diagnostic.message
+        ),
+        None => format!("    {}: {}", heading, diagnostic.message),
+    }
+}
+
+fn to_json(report: &CheckReport) -> Value {
+    let mission = &report.mission;
+    let (errors, warnings) = diagnostics::summarize(&mission.diagnostics);
+    let diagnostics: Vec<Value> = mission
+        .diagnostics
+        .iter()
+        .map(|d| {
+            let span = d.primary_span();
+            json!({
+                "severity": d.severity.label(),
+                "code": d.code,
+                "message": d.message,
+                "file": span.map(|s| &s.file),
+                "line": span.map(|s| s.line),
+                "column": span.map(|s| s.column),
+            })
+        })
+        .collect();
+    let violations: Vec<Value> = mission
+        .violations
+        .iter()
+        .map(|v| {
+            json!({
+                "rule": v.rule.label(),
+                "file": v.file.display().to_string(),
+                "line": v.line,
+                "text": v.text,
+            })
+        })
+        .collect();
+    let tests = report.tests.map(|tests| {
+        json!({
+            "passed": tests.passed,
+            "total": tests.total,
+            "first_failure": tests.first_failure.as_ref().map(|f| json!({
+                "args": f.args,
+                "expected": f.expected,
+                "stdout": f.stdout,
+                "stderr": f.stderr,
+                "exit": f.exit_summary(),
+                "elapsed_us": f.elapsed.as_micros() as u64,
+            })),
+        })
+    });
+
+    let isolation = sandbox::isolation();
+    json!({
+        "mission": {
+            "id": report.manifest.id,
+            "title": report.manifest.title,
+            "source": report.manifest.source,
+        },
+        "passed": report.passed(),
+        "stage": report.stage,
+        "message": report.failure(),
+        "compile": {
+            "errors": errors,
+            "warnings": warnings,
+            "binary_size": mission.binary_size,
+        },
+        "diagnostics": diagnostics,
+        "gates": mission.gates,
+        "violations": violations,
+        "tests": tests,
+        "fault": report.fault,
+        "isolation": {
+            "mode": isolation.name(),
+            "reason": match isolation {
+                Isolation::Fallback(reason) => Some(reason),
+                _ => None,
+            },
+        },
+    })
+}
@@ -0,0 +1,286 @@
+use crate::diagnostics::{self, Diagnostic};
+use crate::job::Job;
+use crate::manifest::RustcFlags;
+use serde::Deserialize;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// What a compile job builds.
+#[derive(Clone)]
+pub enum BuildTarget {
+    /// A single source file, compiled with bare `rustc`.
+    File(PathBuf),
+    /// A Cargo project, identified by its `Cargo.toml`.
+    Cargo(PathBuf),
+}
+
+/// Outcome of a background build.
+pub enum CompileResult {
+    /// Build succeeded, possibly with warnings.
+    Built {
+        binary_size: Option<u64>,
+        diagnostics: Vec<Diagnostic>,
+    },
+    /// The compiler reported errors in the player's code.
+    Rejected(Vec<Diagnostic>),
+    /// The compiler could not be run, or failed without structured output. Carries the message to log.
+    Failed(String),
+    Cancelled,
+}
+
+/// A compilation running on a worker thread. Cancelling kills the compiler;
+/// the job then resolves to `Cancelled`.
+pub type CompileJob = Job<CompileResult>;
+
+/// Builds `target` with the mission's `flags` on a worker thread; the
+/// firmware ends up at `output`.
+pub fn start(target: BuildTarget, output: PathBuf, flags: RustcFlags) -> CompileJob {
+    Job::spawn(
+        move |cancel| match &target {
+            BuildTarget::File(source) => run_rustc(source, &output, &flags, cancel),
+            BuildTarget::Cargo(manifest) => run_cargo(manifest, &output, &flags, cancel),
+        },
+        worker_died,
+    )
+}
+
+fn worker_died() -> CompileResult {
+    CompileResult::Failed("CRITICAL ERROR: Compiler worker thread died.".to_string())
+}
+
+/// One line per compiler gate (see `RustcFlags`) that rejected the build,
+/// so the log says which rule failed rather than just "error".
+pub fn failed_gates(flags: &RustcFlags, diagnostics: &[Diagnostic]) -> Vec<String> {
+    let mut gates = Vec::new();
+    let errors = || diagnostics.iter().filter(|d| d.is_error());
+
+    // rustc only notes "implied by `-D warnings`" on the first hit of each
+    // lint. Collect those lints, then count every error they raised.
+    let lints: Vec<&str> = errors()
+        .filter(|d| {
+            d.children
+                .iter()
+                .any(|c| c.message.contains("implied by `-D warnings`"))
+        })
+        .filter_map(|d| d.code.as_deref())
+        .collect();
+    let promoted = errors()
+        .filter(|d| d.code.as_deref().is_some_and(|code| lints.contains(&code)))
+        .count();
+    if flags.deny_warnings && promoted > 0 {
+        gates.push(format!(
+            "GATE FAILED [-D warnings]: this mission must compile without warnings. \
+             {} warning(s) were promoted to errors.",
+            promoted
+        ));
+    }
+
+    let unsafe_uses = errors()
+        .filter(|d| d.code.as_deref() == Some("unsafe_code"))
+        .count();
+    if flags.forbid_unsafe && unsafe_uses > 0 {
+        gates.push(format!(
+            "GATE FAILED [-F unsafe_code]: `unsafe` is forbidden in this mission. \
+             Found {} use(s).",
+            unsafe_uses
+        ));
+    }
+    gates
+}
+
+fn run_rustc(
+    source: &Path,
+    output: &Path,
+    flags: &RustcFlags,
+    cancel: &AtomicBool,
+) -> CompileResult {
+    if let Err(result) = prepare_output(output) {
+        return result;
+    }
+
+    let mut command = Command::new("rustc");
+    command.arg("--error-format=json");
+    if let Some(edition) = &flags.edition {
+        command.arg(format!("--edition={}", edition));
+    }
+    command
+        .args(flags.codegen_args())
+        .arg(source)
+        .arg("-o")
+        .arg(output);
+    let out = match run_tool(command, "rustc", cancel) {
+        Ok(out) => out,
+        Err(result) => return result,
+    };
+
+    let diagnostics = diagnostics::parse(&out.stderr);
+    if out.status.success() {
+        CompileResult::Built {
+            binary_size: fs::metadata(output).ok().map(|m| m.len()),
+            diagnostics,
+        }
+    } else if diagnostics.iter().any(|d| d.is_error()) {
+        CompileResult::Rejected(diagnostics)
+    } else {
+        CompileResult::Failed(out.stderr)
+    }
+}
+
+/// Builds a Cargo project offline next to `output`, then copies the binary
+/// cargo reports to `output` so the mission runs it like any other firmware.
+/// The mission's flags reach rustc through cargo's rustflags.
+fn run_cargo(
+    manifest: &Path,
+    output: &Path,
+    flags: &RustcFlags,
+    cancel: &AtomicBool,
+) -> CompileResult {
+    if let Err(result) = prepare_output(output) {
+        return result;
+    }
+    let target_dir = output.with_file_name("target");
+
+    let mut command = Command::new("cargo");
+    command
+        .args(["build", "--offline", "--message-format=json"])
+        .arg("--manifest-path")
+        .arg(manifest)
+        .arg("--target-dir")
+        .arg(&target_dir);
+    let rustflags = flags.codegen_args();
+    if !rustflags.is_empty() {
+        // The encoded form survives spaces in values; it also overrides RUSTFLAGS.
+        command.env("CARGO_ENCODED_RUSTFLAGS", rustflags.join("\x1f"));
+    }
+    let out = match run_tool(command, "cargo", cancel) {
+        Ok(out) => out,
+        Err(result) => return result,
+    };
+
+    let project = manifest.parent().unwrap_or(Path::new("."));
+    let diagnostics = diagnostics::parse_cargo(&out.stdout, project);
+    if !out.status.success() {
+        return if diagnostics.iter().any(|d| d.is_error()) {
+            CompileResult::Rejected(diagnostics)
+        } else {
+            // Manifest errors, missing offline dependencies, ...: cargo explains on stderr.
+            CompileResult::Failed(out.stderr)
+        };
+    }
+
+    let Some(executable) = built_executable(&out.stdout) else {
+        return CompileResult::Failed(format!(
+            "CRITICAL ERROR: '{}' built no binary.\nThe project needs a [[bin]] target (src/main.rs).",
+            manifest.display()
+        ));
+    };
+    if let Err(e) = fs::copy(&executable, output) {
+        return CompileResult::Failed(format!(
+            "CRITICAL ERROR: Could not copy '{}' to '{}'.\nDetails: {}",
+            executable.display(),
+            output.display(),
+            e
+        ));
+    }
+    CompileResult::Built {
+        binary_size: fs::metadata(output).ok().map(|m| m.len()),
+        diagnostics,
+    }
+}
+
+/// The executable from the last `compiler-artifact` message cargo printed.
+fn built_executable(stdout: &str) -> Option<PathBuf> {
+    #[derive(Deserialize)]
+    struct Artifact {
+        reason: String,
+        executable: Option<PathBuf>,
+    }
+
+    stdout
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<Artifact>(line).ok())
+        .filter(|a| a.reason == "compiler-artifact")
+        .find_map(|a| a.executable)
+}
+
+fn prepare_output(output: &Path) -> Result<(), CompileResult> {
+    match output.parent() {
+        Some(dir) => fs::create_dir_all(dir).map_err(|e| {
+            CompileResult::Failed(format!(
+                "CRITICAL ERROR: Could not create build directory '{}'.\nDetails: {}",
+                dir.display(),
+                e
+            ))
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Captured output of a finished compiler process.
+struct ToolOutput {
+    status: ExitStatus,
+    stdout: String,
+    stderr: String,
+}
+
+/// Runs `command` to completion, killing it if `cancel` is set. `Err` carries
+/// the result to report instead (cancelled, or the tool could not be run).
+fn run_tool(
+    mut command: Command,
+    name: &str,
+    cancel: &AtomicBool,
+) -> Result<ToolOutput, CompileResult> {
+    let child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = child.map_err(|e| {
+        CompileResult::Failed(format!(
+            "CRITICAL ERROR: Could not run '{}'.\nIs Rust installed?\nDetails: {}",
+            name, e
+        ))
+    })?;
+
+    // Drain both pipes on their own threads so a chatty compiler can't fill one and stall.
+    let stdout = drain(child.stdout.take().expect("stdout is piped"));
+    let stderr = drain(child.stderr.take().expect("stderr is piped"));
+
+    let status = loop {
+        if cancel.load(Ordering::Relaxed) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(CompileResult::Cancelled);
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Err(e) => {
+                return Err(CompileResult::Failed(format!(
+                    "CRITICAL ERROR: Lost track of '{}'.\nDetails: {}",
+                    name, e
+                )))
+            }
+        }
+    };
+
+    Ok(ToolOutput {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+fn drain(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        String::from_utf8_lossy(&buf).to_string()
+    })
+}
@@ -0,0 +1,156 @@
+use std::fmt;
+use std::process::ExitStatus;
+
+/// Where and why the firmware panicked, as printed by the Rust runtime.
+pub struct Panic {
+    pub message: String,
+    /// `file:line:column`, when the runtime printed one.
+    pub location: Option<String>,
+}
+
+/// What is known about firmware that exited abnormally: the decoded exit
+/// status, the panic (if it was one) and the raw stderr with any backtrace.
+pub struct CrashReport {
+    pub exit: String,
+    /// Killed by a signal (segfault, abort, ...) rather than exiting on its own.
+    pub signaled: bool,
+    pub panic: Option<Panic>,
+    pub stderr: String,
+}
+
+impl CrashReport {
+    pub fn new(status: &ExitStatus, stderr: &str) -> Self {
+        Self {
+            exit: describe_status(status),
+            signaled: status.code().is_none(),
+            panic: parse_panic(stderr),
+            stderr: stderr.trim().to_string(),
+        }
+    }
+
+    /// Whether the firmware panicked or was killed, as opposed to simply
+    /// exiting (e.g. closing stdout and returning 0).
+    pub fn is_crash(&self) -> bool {
+        self.panic.is_some() || self.signaled
+    }
+
+    /// Whether stderr already holds a backtrace (`RUST_BACKTRACE` was set).
+    pub fn has_backtrace(&self) -> bool {
+        self.stderr.contains("stack backtrace:")
+    }
+
+    /// Single line for the mission sidebar.
+    pub fn headline(&self) -> String {
+        match &self.panic {
+            Some(Panic {
+                message,
+                location: Some(location),
+            }) => format!("PANIC: {} ({})", message, location),
+            Some(Panic {
+                message,
+                location: None,
+            }) => format!("PANIC: {}", message),
+            None if self.signaled => format!("CRASH: {}", self.exit),
+            None => format!("EXITED: {}", self.exit),
+        }
+    }
+}
+
+impl fmt::Display for CrashReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_crash() {
+            writeln!(f, "FIRMWARE PANIC")?;
+        } else {
+            writeln!(f, "FIRMWARE EXITED")?;
+        }
+        if let Some(panic) = &self.panic {
+            writeln!(f, "  MESSAGE:  {}", panic.message)?;
+            if let Some(location) = &panic.location {
+                writeln!(f, "  LOCATION: {}", location)?;
+            }
+        }
+        write!(f, "  EXIT:     {}", self.exit)?;
+        if !self.stderr.is_empty() {
+            write!(f, "\n\n{}", self.stderr)?;
+        }
+        Ok(())
+    }
+}
+
+/// Human-readable exit status, e.g. "exit code 101" or
+/// "SIGSEGV (segmentation fault)".
+pub fn describe_status(status: &ExitStatus) -> String {
+    if let Some(code) = status.code() {
+        return format!("exit code {}", code);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return match signal_name(signal) {
+                Some((name, meaning)) => format!("{} ({})", name, meaning),
+                None => format!("signal {}", signal),
+            };
+        }
+    }
+    status.to_string()
+}
+
+#[cfg(unix)]
+fn signal_name(signal: i32) -> Option<(&'static str, &'static str)> {
+    Some(match signal {
+        libc::SIGSEGV => ("SIGSEGV", "segmentation fault"),
+        libc::SIGABRT => ("SIGABRT", "aborted"),
+        libc::SIGKILL => ("SIGKILL", "killed, likely out of memory"),
+        libc::SIGBUS => ("SIGBUS", "bus error"),
+        libc::SIGFPE => ("SIGFPE", "arithmetic exception"),
+        libc::SIGILL => ("SIGILL", "illegal instruction"),
+        libc::SIGXCPU => ("SIGXCPU", "CPU time limit exceeded"),
+        _ => return None,
+    })
+}
+
+/// Extracts the panic message and location from stderr. Understands both the
+/// current format (`panicked at src/main.rs:3:5:` followed by the message)
+/// and the pre-1.73 one (`panicked at 'message', src/main.rs:3:5`). Also
+/// recognises the runtime's own fatal messages.
+fn parse_panic(stderr: &str) -> Option<Panic> {
+    let mut lines = stderr.lines();
+    while let Some(line) = lines.next() {
+        // Newer toolchains also print the thread id: `thread 'main' (1234) panicked at`.
+        let panicked = line
+            .starts_with("thread '")
+            .then(|| line.split_once(" panicked at "))
+            .flatten();
+        if let Some((_, rest)) = panicked {
+            if let Some(location) = rest.strip_suffix(':') {
+                let message: Vec<&str> = lines
+                    .take_while(|l| !l.starts_with("note: ") && !l.starts_with("stack backtrace:"))
+                    .collect();
+                return Some(Panic {
+                    message: message.join("\n"),
+                    location: Some(location.to_string()),
+                });
+            }
+            if let Some((message, location)) =
+                rest.strip_prefix('\'').and_then(|r| r.rsplit_once("', "))
+            {
+                return Some(Panic {
+                    message: message.to_string(),
+                    location: Some(location.to_string()),
+                });
+            }
+            return Some(Panic {
+                message: rest.to_string(),
+                location: None,
+            });
+        }
+        if line.contains("has overflowed its stack") || line.starts_with("memory allocation of ") {
+            return Some(Panic {
+                message: line.to_string(),
+                location: None,
+            });
+        }
+    }
+    None
+}
@@ -0,0 +1,214 @@
+use serde::Deserialize;
+use std::path::Path;
+
+/// How serious a compiler message is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+impl Severity {
+    fn parse(level: &str) -> Option<Self> {
+        match level {
+            "error" | "error: internal compiler error" => Some(Severity::Error),
+            "warning" => Some(Severity::Warning),
+            "note" => Some(Severity::Note),
+            "help" => Some(Severity::Help),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+            Severity::Help => "help",
+        }
+    }
+}
+
+/// One compiler message, parsed from `rustc --error-format=json`.
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Error code such as `E0308`, if rustc assigned one.
+    pub code: Option<String>,
+    pub message: String,
+    pub spans: Vec<Span>,
+    /// Attached `note:` / `help:` messages, including fix suggestions.
+    pub children: Vec<Suggestion>,
+}
+
+/// A source location a diagnostic points at.
+#[derive(Clone, Debug)]
+pub struct Span {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    /// Exclusive end column on the first line, used to size the caret underline.
+    pub column_end: usize,
+    pub is_primary: bool,
+    pub label: Option<String>,
+    /// Text of the first line of the span, if rustc included it.
+    pub source_line: Option<String>,
+}
+
+/// A child message, optionally carrying a concrete replacement.
+#[derive(Clone, Debug)]
+pub struct Suggestion {
+    pub severity: Severity,
+    pub message: String,
+    pub replacement: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// The span rustc considers the main location of the problem.
+    pub fn primary_span(&self) -> Option<&Span> {
+        self.spans.iter().find(|s| s.is_primary)
+    }
+
+    /// The primary span, if it points into `file`.
+    pub fn primary_span_in(&self, file: &Path) -> Option<&Span> {
+        self.primary_span()
+            .filter(|span| Path::new(&span.file) == file)
+    }
+}
+
+/// Parses rustc's JSON stderr. Lines that aren't diagnostics are skipped, as are
+/// the "aborting due to" / "N warnings emitted" / "For more information" trailers.
+pub fn parse(stderr: &str) -> Vec<Diagnostic> {
+    convert(
+        stderr
+            .lines()
+            .filter_map(|line| serde_json::from_str::<RawDiagnostic>(line).ok()),
+    )
+}
+
+/// Parses the `compiler-message` entries of `cargo build --message-format=json`
+/// stdout. Each one wraps a rustc diagnostic. Cargo reports files relative to
+/// the package, so they are resolved against `project` to match mission paths.
+pub fn parse_cargo(stdout: &str, project: &Path) -> Vec<Diagnostic> {
+    let mut diagnostics = convert(
+        stdout
+            .lines()
+            .filter_map(|line| serde_json::from_str::<CargoMessage>(line).ok())
+            .filter(|m| m.reason == "compiler-message")
+            .filter_map(|m| m.message),
+    );
+    for span in diagnostics.iter_mut().flat_map(|d| d.spans.iter_mut()) {
+        if Path::new(&span.file).is_relative() {
+            span.file = project.join(&span.file).display().to_string();
+        }
+    }
+    diagnostics
+}
+
+fn convert(raw: impl Iterator<Item = RawDiagnostic>) -> Vec<Diagnostic> {
+    raw.filter(|raw| !raw.message.starts_with("aborting due to"))
+        .filter(|raw| !raw.message.ends_with("emitted"))
+        .filter_map(RawDiagnostic::into_diagnostic)
+        .collect()
+}
+
+/// Counts `(errors, warnings)`.
+pub fn summarize(diagnostics: &[Diagnostic]) -> (usize, usize) {
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+    let warnings = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Warning)
+        .count();
+    (errors, warnings)
+}
+
+// --- rustc JSON schema (only the fields we use) ---
+
+#[derive(Deserialize)]
+struct RawDiagnostic {
+    message: String,
+    code: Option<RawCode>,
+    level: String,
+    #[serde(default)]
+    spans: Vec<RawSpan>,
+    #[serde(default)]
+    children: Vec<RawDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<RawDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct RawCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RawSpan {
+    file_name: String,
+    line_start: usize,
+    column_start: usize,
+    column_end: usize,
+    is_primary: bool,
+    #[serde(default)]
+    text: Vec<RawSpanLine>,
+    label: Option<String>,
+    suggested_replacement: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RawSpanLine {
+    text: String,
+}
+
+impl RawDiagnostic {
+    fn into_diagnostic(self) -> Option<Diagnostic> {
+        let severity = Severity::parse(&self.level)?;
+        let children = self
+            .children
+            .into_iter()
+            .filter_map(|child| {
+                let replacement = child
+                    .spans
+                    .iter()
+                    .find_map(|s| s.suggested_replacement.clone());
+                Some(Suggestion {
+                    severity: Severity::parse(&child.level)?,
+                    message: child.message,
+                    replacement,
+                })
+            })
+            .collect();
+
+        Some(Diagnostic {
+            severity,
+            code: self.code.map(|c| c.code),
+            message: self.message,
+            spans: self.spans.into_iter().map(RawSpan::into_span).collect(),
+            children,
+        })
+    }
+}
+
+impl RawSpan {
+    fn into_span(self) -> Span {
+        Span {
+            file: self.file_name,
+            line: self.line_start,
+            column: self.column_start,
+            column_end: self.column_end,
+            is_primary: self.is_primary,
+            label: self.label,
+            source_line: self.text.into_iter().next().map(|l| l.text),
+        }
+    }
+}
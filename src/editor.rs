@@ -0,0 +1,62 @@
+use std::env;
+use std::io;
+use std::path::Path;
+use std::process::{Command, ExitStatus};
+
+/// Editors tried when neither `$VISUAL` nor `$EDITOR` is set.
+const FALLBACKS: &[&str] = &["nano", "vi"];
+
+/// Opens `path` in the player's editor, at `line` when given, and waits for it
+/// to exit. The terminal must already be out of raw mode.
+pub fn open(path: &Path, line: Option<usize>) -> io::Result<ExitStatus> {
+    let configured = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.trim().is_empty());
+    if let Some(editor) = configured {
+        return launch(&editor, path, line);
+    }
+
+    for editor in FALLBACKS {
+        match launch(editor, path, line) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            result => return result,
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "no editor found: set $VISUAL or $EDITOR",
+    ))
+}
+
+/// Runs `editor` (which may carry its own flags, e.g. `code --wait`) with the
+/// line-jump syntax its family understands.
+fn launch(editor: &str, path: &Path, line: Option<usize>) -> io::Result<ExitStatus> {
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or(editor);
+    let mut command = Command::new(program);
+    command.args(words);
+
+    let name = Path::new(program)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(program);
+    match (name, line) {
+        (_, None) => {
+            command.arg(path);
+        }
+        ("code" | "codium" | "code-insiders", Some(line)) => {
+            command
+                .arg("--goto")
+                .arg(format!("{}:{}", path.display(), line));
+        }
+        ("subl" | "zed" | "hx" | "helix", Some(line)) => {
+            command.arg(format!("{}:{}", path.display(), line));
+        }
+        // vi, vim, nvim, nano, emacs, micro, kak...
+        (_, Some(line)) => {
+            command.arg(format!("+{}", line)).arg(path);
+        }
+    }
+    command.status()
+}
@@ -0,0 +1,200 @@
+use crate::crash::CrashReport;
+use crate::runner::{self, Limits, RunError};
+use crate::sandbox::{self, Scratch};
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// A player binary kept alive between queries (`protocol = "stdio"`).
+///
+/// The firmware is started as `<binary> --stdio` and must answer every
+/// line written to its stdin with exactly one line on stdout. If it exits or
+/// stops answering within `limits.wall_time` it is killed and transparently
+/// restarted on the next query.
+pub struct FirmwareProcess {
+    binary: String,
+    limits: Limits,
+    running: Option<Running>,
+    /// How many times the firmware had to be relaunched after a crash or hang.
+    pub restarts: u32,
+}
+
+/// Why a query got no answer.
+pub enum QueryError {
+    /// The firmware exited on its own: a panic, a signal or a plain non-zero exit.
+    Crashed(CrashReport),
+    /// Watchdog reset, launch failure or protocol violation.
+    Fault(String),
+}
+
+impl QueryError {
+    /// Single line for the mission sidebar.
+    pub fn headline(&self) -> String {
+        match self {
+            QueryError::Crashed(report) => report.headline(),
+            QueryError::Fault(message) => message.clone(),
+        }
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::Crashed(report) => report.fmt(f),
+            QueryError::Fault(message) => f.write_str(message),
+        }
+    }
+}
+
+struct Running {
+    child: Child,
+    stdin: ChildStdin,
+    replies: Receiver<Result<String, RunError>>,
+    stderr: JoinHandle<String>,
+    /// Sandbox working directory, removed once the process is gone.
+    _scratch: Option<Scratch>,
+}
+
+impl FirmwareProcess {
+    pub fn new(binary: &str, limits: Limits) -> Self {
+        Self {
+            binary: binary.to_string(),
+            limits,
+            running: None,
+            restarts: 0,
+        }
+    }
+
+    /// Sends one query line and waits for the reply line.
+    pub fn query(&mut self, request: &str) -> Result<String, QueryError> {
+        if self.running.is_none() {
+            self.running = Some(self.launch()?);
+        }
+        let running = self.running.as_mut().expect("launched above");
+
+        // A failed write means the process already died; relaunch and retry once.
+        if writeln!(running.stdin, "{}", request).is_err() {
+            self.crashed();
+            self.running = Some(self.launch()?);
+            let running = self.running.as_mut().expect("launched above");
+            if writeln!(running.stdin, "{}", request).is_err() {
+                return Err(self.reap());
+            }
+        }
+        let running = self.running.as_mut().expect("launched above");
+
+        match running.replies.recv_timeout(self.limits.wall_time) {
+            Ok(Ok(reply)) => Ok(reply),
+            Ok(Err(e)) => {
+                self.crashed();
+                Err(QueryError::Fault(e.to_string()))
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                self.crashed();
+                Err(QueryError::Fault(
+                    RunError::Timeout(self.limits.wall_time).to_string(),
+                ))
+            }
+            Err(RecvTimeoutError::Disconnected) => Err(self.reap()),
+        }
+    }
+
+    fn launch(&self) -> Result<Running, QueryError> {
+        let exec_err = |_: std::io::Error| QueryError::Fault("EXEC_ERR".to_string());
+        let (mut command, scratch) = sandbox::command(&self.binary).map_err(exec_err)?;
+        // The process can't be replayed after a panic, so always ask for the trace.
+        command
+            .arg("--stdio")
+            .env("RUST_BACKTRACE", "1")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        runner::apply_limits(&mut command, &self.limits);
+        let mut child = command.spawn().map_err(exec_err)?;
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let (stderr, _) = runner::capture(
+            child.stderr.take().expect("stderr is piped"),
+            self.limits.max_output,
+        );
+
+        // Forward reply lines so queries can wait with a timeout. A line longer
+        // than `max_output` is reported instead of being buffered forever.
+        let max_line = self.limits.max_output;
+        let (sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            loop {
+                let mut line = String::new();
+                match (&mut reader).take(max_line as u64 + 1).read_line(&mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) if !line.ends_with('\n') && line.len() > max_line => {
+                        let _ = sender.send(Err(RunError::OutputLimit(max_line)));
+                        break;
+                    }
+                    Ok(_) => {
+                        let reply = line.trim_end_matches(['\r', '\n']).to_string();
+                        if sender.send(Ok(reply)).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Ok(Running {
+            child,
+            stdin,
+            replies,
+            stderr,
+            _scratch: scratch,
+        })
+    }
+
+    /// Collects the exit status and stderr of a process that closed its
+    /// stdout, giving it `wall_time` to actually exit.
+    fn reap(&mut self) -> QueryError {
+        let Some(mut running) = self.running.take() else {
+            return QueryError::Fault("CRASH".to_string());
+        };
+        self.restarts += 1;
+
+        let deadline = Instant::now() + self.limits.wall_time;
+        let status = loop {
+            match running.child.try_wait() {
+                Ok(Some(status)) => break Some(status),
+                Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(1)),
+                _ => break None,
+            }
+        };
+        let Some(status) = status else {
+            let _ = running.child.kill();
+            let _ = running.child.wait();
+            return QueryError::Fault("CRASH: firmware closed its output".to_string());
+        };
+        let stderr = running.stderr.join().unwrap_or_default();
+        QueryError::Crashed(CrashReport::new(&status, &stderr))
+    }
+
+    /// Kills the current process; the next query relaunches it.
+    fn crashed(&mut self) {
+        if let Some(mut running) = self.running.take() {
+            let _ = running.child.kill();
+            let _ = running.child.wait();
+            self.restarts += 1;
+        }
+    }
+}
+
+impl Drop for FirmwareProcess {
+    fn drop(&mut self) {
+        if let Some(mut running) = self.running.take() {
+            let _ = running.child.kill();
+            let _ = running.child.wait();
+        }
+    }
+}
@@ -1,8 +1,18 @@
-use crate::levels::mission_01::Mission01State;
-use crate::levels::mission_02::Mission02State;
+use crate::artifacts;
+use crate::compiler::{self, BuildTarget, CompileJob, CompileResult};
+use crate::diagnostics::{self, Diagnostic};
+use crate::harness;
+use crate::manifest::{MissionKind, MissionManifest, RustcFlags};
+use crate::rules::{self, Rule, Violation};
+use crate::runner::RunError;
+use crate::setup;
+use crate::suite::{SuiteReport, TestSuite};
+use crossterm::event::KeyCode;
+use ratatui::{layout::Rect, Frame};
 use std::fs;
-use std::path::Path;
-use std::process::Command;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[derive(Clone, PartialEq)]
 pub enum MissionStatus {
@@ -14,84 +24,255 @@ pub enum MissionStatus {
 
 pub struct Mission {
     pub id: u32,
-    pub title: &'static str,
-    pub description: &'static str,
-    pub path: &'static str,
+    pub title: String,
+    #[allow(dead_code)]
+    pub description: String,
+    pub path: PathBuf,
+    /// Where the compiled firmware is written (see `artifacts::binary_path`).
+    pub binary_path: PathBuf,
+    /// What gets compiled: `path` itself, or the Cargo project containing it.
+    pub build: BuildTarget,
+    /// Compiler settings and lint gates from the manifest.
+    pub flags: RustcFlags,
     pub status: MissionStatus,
     // Added to satisfy UI requirements (Option<u64> allows None if no binary exists)
     pub binary_size: Option<u64>,
+    /// Errors and warnings from the last compile.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Which compiler gates (`-D warnings`, ...) rejected the last compile.
+    pub gates: Vec<String>,
+    /// Constructs banned by the manifest.
+    pub banned: Vec<Rule>,
+    /// Banned constructs found after the last successful compile.
+    pub violations: Vec<Violation>,
 }
 
 impl Mission {
-    pub fn new(
-        id: u32,
-        title: &'static str,
-        description: &'static str,
-        path: &'static str,
-    ) -> Self {
+    pub fn new(manifest: &MissionManifest) -> Self {
         Self {
-            id,
-            title,
-            description,
-            path,
+            id: manifest.id,
+            title: manifest.title.clone(),
+            description: manifest.description.clone(),
+            path: manifest.source.clone(),
+            binary_path: artifacts::binary_path(manifest),
+            build: match (manifest.kind, &manifest.project) {
+                (MissionKind::Cargo, Some(project)) => {
+                    BuildTarget::Cargo(project.join("Cargo.toml"))
+                }
+                _ => BuildTarget::File(manifest.source.clone()),
+            },
+            flags: manifest.rustc.clone(),
             status: MissionStatus::Active,
             binary_size: None,
+            diagnostics: Vec::new(),
+            gates: Vec::new(),
+            banned: manifest.banned.clone(),
+            violations: Vec::new(),
         }
     }
 
-    /// Attempts to compile the mission source code located at `self.path`.
-    pub fn compile_binary(&mut self, output_name: &str) -> bool {
-        let source_path = Path::new(self.path);
-
+    /// Starts compiling the mission (see `self.build`) on a worker thread.
+    /// Returns `None` (with `status` set to `Failed`) if the source file is missing.
+    pub fn start_compile(&mut self) -> Option<CompileJob> {
         // 1. Check if file exists locally
-        if !source_path.exists() {
+        if !self.path.exists() {
             self.status = MissionStatus::Failed(format!(
                 "ERROR: File not found: {}\n\nDid you delete it? Run --init to restore.",
-                self.path
+                self.path.display()
             ));
             self.binary_size = None;
-            return false;
+            self.diagnostics.clear();
+            self.gates.clear();
+            return None;
         }
 
-        // 2. Invoke rustc
-        let output = Command::new("rustc")
-            .arg(source_path)
-            .arg("-o")
-            .arg(output_name)
-            .output();
-
-        match output {
-            Ok(o) => {
-                if o.status.success() {
-                    self.status = MissionStatus::Success;
-
-                    // Update binary size for the UI
-                    if let Ok(metadata) = fs::metadata(output_name) {
-                        self.binary_size = Some(metadata.len());
-                    }
-
-                    return true;
-                } else {
-                    let stderr = String::from_utf8_lossy(&o.stderr);
-                    self.status = MissionStatus::Failed(stderr.to_string());
-                    self.binary_size = None;
-                    return false;
-                }
+        // 2. Invoke rustc (or cargo) in the background
+        Some(compiler::start(
+            self.build.clone(),
+            self.binary_path.clone(),
+            self.flags.clone(),
+        ))
+    }
+
+    /// Refuses verification if the protected harness regions differ from the
+    /// shipped mission. Missions without an embedded original are not checked.
+    pub fn verify_harness(&self) -> Result<(), String> {
+        let key = setup::mission_key(&self.path);
+        let Some(original) = setup::original_source(&key) else {
+            return Ok(());
+        };
+        let current = fs::read_to_string(&self.path).map_err(|e| {
+            format!(
+                "ERROR: Could not read {}.\n\nDetails: {}",
+                self.path.display(),
+                e
+            )
+        })?;
+        // Extracted before the markers shipped and kept by --upgrade because
+        // it holds the player's work: there is no harness to compare yet.
+        if harness::protected_ranges(&current).is_empty() && !setup::is_recorded(&key) {
+            return Ok(());
+        }
+        harness::check(original, &current)
+    }
+
+    /// Scans the player's code for constructs the mission bans: the source
+    /// file, or every `.rs` file under `src/` of a Cargo project. Hits are kept
+    /// in `violations`; in hardcore mode any hit fails the mission. Expects
+    /// `verify_harness` to have passed: the harness of the mission file is
+    /// not scanned, every other file is scanned in full.
+    pub fn check_rules(&mut self) -> Result<(), String> {
+        self.violations.clear();
+        if self.banned.is_empty() {
+            return Ok(());
+        }
+        let files = match &self.build {
+            BuildTarget::File(source) => vec![source.clone()],
+            BuildTarget::Cargo(manifest) => {
+                let src = manifest.with_file_name("src");
+                let mut files = Vec::new();
+                rust_sources(&src, &mut files).map_err(|e| {
+                    format!("ERROR: Could not read {}.\n\nDetails: {}", src.display(), e)
+                })?;
+                files.sort();
+                files
             }
-            Err(e) => {
-                self.status = MissionStatus::Failed(format!(
-                    "CRITICAL ERROR: Could not run 'rustc'.\nIs Rust installed?\nDetails: {}",
+        };
+        let checked = setup::original_source(&setup::mission_key(&self.path)).is_some();
+        for file in files {
+            let source = fs::read_to_string(&file).map_err(|e| {
+                format!(
+                    "ERROR: Could not read {}.\n\nDetails: {}",
+                    file.display(),
                     e
-                ));
+                )
+            })?;
+            let skip_harness = checked && file == self.path;
+            self.violations
+                .extend(rules::scan(&file, &source, &self.banned, skip_harness));
+        }
+        if self.violations.is_empty() || !rules::hardcore() {
+            return Ok(());
+        }
+        let mut message = format!(
+            "PERMADEATH: {} banned construct(s) in your firmware. Hardcore mode allows none.\n",
+            self.violations.len()
+        );
+        for violation in &self.violations {
+            message.push_str(&format!("\n  {}", violation));
+        }
+        Err(message)
+    }
+
+    /// Applies the result of a finished compile job. Returns `true` if a fresh
+    /// binary is ready. A cancelled build leaves the previous binary untouched.
+    pub fn finish_compile(&mut self, result: CompileResult) -> bool {
+        match result {
+            CompileResult::Built {
+                binary_size,
+                diagnostics,
+            } => {
+                self.status = MissionStatus::Success;
+                // Update binary size for the UI
+                self.binary_size = binary_size;
+                self.diagnostics = diagnostics;
+                self.gates.clear();
+                true
+            }
+            CompileResult::Rejected(diagnostics) => {
+                let (errors, warnings) = diagnostics::summarize(&diagnostics);
+                self.gates = compiler::failed_gates(&self.flags, &diagnostics);
+                let mut message = format!(
+                    "COMPILATION FAILED: {} ERROR(S), {} WARNING(S).",
+                    errors, warnings
+                );
+                for gate in &self.gates {
+                    message.push_str("\n\n");
+                    message.push_str(gate);
+                }
+                self.status = MissionStatus::Failed(message);
+                self.binary_size = None;
+                self.diagnostics = diagnostics;
+                false
+            }
+            CompileResult::Failed(message) => {
+                self.status = MissionStatus::Failed(message);
                 self.binary_size = None;
-                return false;
+                self.diagnostics.clear();
+                self.gates.clear();
+                false
+            }
+            CompileResult::Cancelled => {
+                self.status = MissionStatus::Active;
+                false
             }
         }
     }
 }
 
-pub enum GameState {
-    MainMenu,
-    Mission01(Mission01State),
-    Mission02(Mission02State),
+/// Collects every `.rs` file below `dir`.
+fn rust_sources(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            rust_sources(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Gameplay logic for a single mission.
+///
+/// `App` only talks to the active mission through this trait, so a new level
+/// is a new implementor plus an entry in `levels::LOGIC`.
+pub trait MissionState {
+    /// Builds the starting state when the mission is loaded.
+    fn init(manifest: &MissionManifest) -> Self
+    where
+        Self: Sized;
+
+    /// Runs when a compile starts. Results of the previous build (test report,
+    /// faults) must not be shown next to the new build's outcome.
+    fn on_compile_start(&mut self);
+
+    /// The hidden tests a fresh build must pass. They run on a worker thread
+    /// (see `suite::start`); the outcome goes to `on_compile_success`.
+    fn test_suite(&self) -> TestSuite;
+
+    /// Runs after a successful compile once the hidden tests finished. Returning
+    /// `Err` marks the mission as failed with the given message (e.g. the binary
+    /// compiled but the logic is wrong).
+    fn on_compile_success(&mut self, tests: Result<SuiteReport, RunError>) -> Result<(), String>;
+
+    /// Runs when compilation or verification fails.
+    fn on_compile_fail(&mut self);
+
+    /// Mission specific controls (only called while the MISSION tab is active).
+    fn on_input(&mut self, _key_code: KeyCode) {}
+
+    fn render(&self, f: &mut Frame, area: Rect);
+
+    fn is_finished(&self) -> bool;
+
+    /// Last runtime fault (watchdog reset, crash) reported while playing.
+    fn fault(&self) -> Option<&str> {
+        None
+    }
+
+    /// Hidden test suite results from the last verification, if any.
+    fn test_report(&self) -> Option<&SuiteReport> {
+        None
+    }
+
+    /// Fastest firmware run this session, recorded in the save file on completion.
+    fn best_latency(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Footer text shown on the MISSION tab.
+    fn footer_hints(&self) -> &'static str {
+        " [C] Compile Code | [E] Edit | [TAB] View Logs "
+    }
 }
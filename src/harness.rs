@@ -0,0 +1,322 @@
+use crate::rules;
+use std::ops::Range;
+
+/// Opens a protected region. The marker line itself is part of the region.
+pub const BEGIN_MARKER: &str = "DO NOT EDIT THE CODE BELOW THIS LINE";
+/// Closes a protected region, inclusive.
+pub const END_MARKER: &str = "DO NOT EDIT THE CODE ABOVE THIS LINE";
+
+/// Zero-based line ranges of the "DO NOT EDIT" harness regions in a mission
+/// source. An unterminated region runs to the end of the file.
+pub fn protected_ranges(source: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+    let mut count = 0;
+    for (i, line) in source.lines().enumerate() {
+        count = i + 1;
+        match start {
+            None if line.contains(BEGIN_MARKER) => start = Some(i),
+            Some(begin) if line.contains(END_MARKER) => {
+                ranges.push(begin..i + 1);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(begin) = start {
+        ranges.push(begin..count);
+    }
+    ranges
+}
+
+/// Compares the protected regions of `current` against those of `original`,
+/// along with the briefing above the first one, so nothing can be slipped in
+/// front of the harness. Returns a report with a line diff if the player
+/// changed them. Trailing whitespace and blank briefing lines are ignored so
+/// editors that strip them don't trip the check.
+pub fn check(original: &str, current: &str) -> Result<(), String> {
+    let expected = regions(original);
+    if expected.is_empty() {
+        return Ok(());
+    }
+    let actual = regions(current);
+    let (old_briefing, new_briefing) = (briefing(original), briefing(current));
+    if expected == actual && old_briefing == new_briefing {
+        return top_level(current);
+    }
+
+    let mut report = String::from(
+        "TAMPERING DETECTED: the SYSTEM HARDWARE INTERFACE was modified.\n\
+         Verification refused. Only edit the firmware section, then restore\n\
+         the interface below ('-' original, '+' yours), or press [R] to reset\n\
+         the whole file (your code is backed up first).\n",
+    );
+    if expected.len() != actual.len() {
+        report.push_str(&format!(
+            "\nExpected {} protected region(s), found {}. Were the markers removed?\n",
+            expected.len(),
+            actual.len()
+        ));
+    }
+    let old: Vec<&str> = old_briefing
+        .into_iter()
+        .chain(expected.into_iter().flatten())
+        .collect();
+    let new: Vec<&str> = new_briefing
+        .into_iter()
+        .chain(actual.into_iter().flatten())
+        .collect();
+    report.push('\n');
+    report.push_str(diff(&old, &new).trim_end());
+    Err(report)
+}
+
+/// The trimmed lines of each protected region.
+fn regions(source: &str) -> Vec<Vec<&str>> {
+    let lines: Vec<&str> = source.lines().map(str::trim_end).collect();
+    protected_ranges(source)
+        .into_iter()
+        .map(|range| lines[range].to_vec())
+        .collect()
+}
+
+/// The non-blank, trimmed lines above the first protected region (the mission
+/// briefing). Empty if there is no region.
+fn briefing(source: &str) -> Vec<&str> {
+    let Some(first) = protected_ranges(source).first().map(|range| range.start) else {
+        return Vec::new();
+    };
+    source
+        .lines()
+        .take(first)
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+/// Makes sure every protected region, and with it the harness `fn main`, is
+/// still compiled as written: not inside a block or module opened in the
+/// player's code, and not under an attribute such as `#[cfg(any())]`.
+fn top_level(current: &str) -> Result<(), String> {
+    let code: Vec<String> = rules::strip_comments_and_literals(current)
+        .lines()
+        .map(str::to_string)
+        .collect();
+    for range in protected_ranges(current) {
+        let before = &code[..range.start.min(code.len())];
+        let depth: i64 = before
+            .iter()
+            .flat_map(|line| line.chars())
+            .map(|c| match c {
+                '{' | '(' | '[' => 1,
+                '}' | ')' | ']' => -1,
+                _ => 0,
+            })
+            .sum();
+        let attribute = before
+            .iter()
+            .rev()
+            .map(|line| line.trim_end())
+            .find(|line| !line.is_empty())
+            .is_some_and(|line| line.ends_with(']'));
+        if depth != 0 || attribute {
+            let why = if depth != 0 {
+                "sits inside a block opened in your code"
+            } else {
+                "is under an attribute added in your code"
+            };
+            return Err(format!(
+                "TAMPERING DETECTED: the SYSTEM HARDWARE INTERFACE at line {} {}.\n\
+                 Verification refused. The interface and its `fn main` must stay at\n\
+                 the top level of the file, exactly as shipped. Press [R] to reset\n\
+                 the whole file (your code is backed up first).",
+                range.start + 1,
+                why
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Lines of context kept around each change.
+const CONTEXT: usize = 1;
+
+/// A minimal line diff (longest common subsequence) showing only changed
+/// lines and their immediate context.
+fn diff(old: &[&str], new: &[&str]) -> String {
+    // lcs[i][j]: length of the LCS of old[i..] and new[j..].
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(('-', old[i]));
+            i += 1;
+        } else {
+            ops.push(('+', new[j]));
+            j += 1;
+        }
+    }
+
+    let near_change = |k: usize| {
+        let from = k.saturating_sub(CONTEXT);
+        let to = (k + CONTEXT + 1).min(ops.len());
+        ops[from..to].iter().any(|(tag, _)| *tag != ' ')
+    };
+    let mut out = String::new();
+    let mut skipped = false;
+    for (k, (tag, line)) in ops.iter().enumerate() {
+        if near_change(k) {
+            if skipped && !out.is_empty() {
+                out.push_str("  ...\n");
+            }
+            skipped = false;
+            out.push_str(&format!("{} {}\n", tag, line));
+        } else {
+            skipped = true;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A mission file laid out like the shipped ones: briefing, harness,
+    /// the player's section, then a second harness region.
+    fn mission(briefing: &str, first: &str, player: &str, second: &str) -> String {
+        format!(
+            "{briefing}\n\n// {BEGIN_MARKER}\n{first}\n// {END_MARKER}\n\n\
+             {player}\n\n// {BEGIN_MARKER}\n{second}\n// {END_MARKER}\n"
+        )
+    }
+
+    fn original() -> String {
+        mission(
+            "// MISSION: keep the pumps running.",
+            "fn main() {\n    println!(\"{}\", pump());\n}",
+            "fn pump() -> u32 {\n    0\n}",
+            "fn sensor() -> u32 {\n    42\n}",
+        )
+    }
+
+    #[test]
+    fn finds_protected_ranges() {
+        let source = format!("a\n// {BEGIN_MARKER}\nb\n// {END_MARKER}\nc\n// {BEGIN_MARKER}\nd\n");
+        assert_eq!(protected_ranges(&source), vec![1..4, 5..7]);
+        assert!(protected_ranges("fn main() {}\n").is_empty());
+    }
+
+    #[test]
+    fn accepts_untouched_harness() {
+        let source = original();
+        assert_eq!(check(&source, &source), Ok(()));
+
+        let solved = source.replace("    0\n", "    let level = 3;\n    level * 2\n");
+        assert_eq!(check(&source, &solved), Ok(()));
+    }
+
+    #[test]
+    fn ignores_trailing_whitespace_and_blank_briefing_lines() {
+        let source = original();
+        let edited = source
+            .replace("fn main() {", "fn main() {   ")
+            .replace("// MISSION", "\n// MISSION");
+        assert_eq!(check(&source, &edited), Ok(()));
+    }
+
+    #[test]
+    fn rejects_tampered_region() {
+        let source = original();
+        let tampered = source.replace("    42", "    0");
+        let report = check(&source, &tampered).unwrap_err();
+        assert!(report.contains("TAMPERING DETECTED"));
+        assert!(report.contains("-     42"));
+        assert!(report.contains("+     0"));
+    }
+
+    #[test]
+    fn rejects_reordered_regions() {
+        let source = original();
+        let reordered = mission(
+            "// MISSION: keep the pumps running.",
+            "fn sensor() -> u32 {\n    42\n}",
+            "fn pump() -> u32 {\n    0\n}",
+            "fn main() {\n    println!(\"{}\", pump());\n}",
+        );
+        assert!(check(&source, &reordered).is_err());
+    }
+
+    #[test]
+    fn rejects_changed_briefing() {
+        let source = original();
+        let edited = source.replace("// MISSION", "#![allow(unused)]\n// MISSION");
+        assert!(check(&source, &edited).is_err());
+    }
+
+    #[test]
+    fn rejects_removed_markers() {
+        let source = original();
+        let stripped = source.replace(BEGIN_MARKER, "").replace(END_MARKER, "");
+        let report = check(&source, &stripped).unwrap_err();
+        assert!(report.contains("Expected 2 protected region(s), found 0"));
+    }
+
+    #[test]
+    fn rejects_region_inside_a_block() {
+        let source = original();
+        let wrapped = source.replace(
+            "fn pump() -> u32 {\n    0\n}",
+            "fn pump() -> u32 {\n    0\n}\nmod hidden {",
+        ) + "}\n";
+        let report = check(&source, &wrapped).unwrap_err();
+        assert!(report.contains("inside a block"));
+    }
+
+    #[test]
+    fn rejects_region_under_an_attribute() {
+        let source = original();
+        let disabled = source.replace("    0\n}", "    0\n}\n#[cfg(any())]");
+        let report = check(&source, &disabled).unwrap_err();
+        assert!(report.contains("under an attribute"));
+    }
+
+    #[test]
+    fn braces_in_literals_and_comments_are_not_blocks() {
+        let source = original();
+        let solved = source.replace(
+            "    0\n",
+            "    let _open = \"{ ( [\";\n    let _brace = '{';\n    // }\n    0\n",
+        );
+        assert_eq!(check(&source, &solved), Ok(()));
+    }
+
+    #[test]
+    fn shipped_missions_pass_their_own_check() {
+        for key in ["01_shelter.rs", "02_water.rs"] {
+            let source = crate::setup::original_source(key).unwrap();
+            assert!(!protected_ranges(source).is_empty(), "{key}");
+            assert_eq!(check(source, source), Ok(()), "{key}");
+        }
+    }
+
+    #[test]
+    fn file_without_harness_is_not_checked() {
+        assert_eq!(check("fn main() {}\n", "fn main() { loop {} }\n"), Ok(()));
+    }
+}
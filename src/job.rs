@@ -0,0 +1,60 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Handle to work running on a worker thread (a compile, a hidden test run),
+/// so the UI keeps drawing while it runs.
+pub struct Job<T> {
+    receiver: Receiver<T>,
+    cancel: Arc<AtomicBool>,
+    started: Instant,
+    /// Stands in for the result if the worker thread died.
+    died: fn() -> T,
+}
+
+impl<T: Send + 'static> Job<T> {
+    /// Runs `work` on a worker thread. `work` gets the cancel flag and should
+    /// stop early once it is set.
+    pub fn spawn(work: impl FnOnce(&AtomicBool) -> T + Send + 'static, died: fn() -> T) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let worker_cancel = Arc::clone(&cancel);
+
+        thread::spawn(move || {
+            // The receiver is gone if the job was dropped or the game quit.
+            let _ = sender.send(work(&worker_cancel));
+        });
+
+        Self {
+            receiver,
+            cancel,
+            started: Instant::now(),
+            died,
+        }
+    }
+
+    /// Asks the worker to stop. What it returns then depends on the work.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    /// Non-blocking check for the result. Called once per frame by the event loop.
+    pub fn try_result(&self) -> Option<T> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some((self.died)()),
+        }
+    }
+
+    /// Blocks until the worker finishes. Used by the headless `check` command.
+    pub fn wait(self) -> T {
+        self.receiver.recv().unwrap_or_else(|_| (self.died)())
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
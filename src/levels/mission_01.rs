@@ -1,5 +1,14 @@
+use crate::artifacts;
+use crate::crash::CrashReport;
+use crate::firmware::{FirmwareProcess, QueryError};
+use crate::gameplay::MissionState;
+use crate::manifest::{MissionManifest, Protocol};
+use crate::runner::{self, Limits, RunError};
+use crate::suite::{self, SuiteReport, TestCase, TestSuite};
+use crate::ui;
+use crossterm::event::KeyCode;
 use rand::Rng;
-use std::process::Command;
+use ratatui::{layout::Rect, Frame};
 use std::time::{Duration, Instant};
 
 #[derive(Clone, Copy, PartialEq)]
@@ -10,7 +19,6 @@ pub enum TileType {
     Ruin,
 }
 
-#[derive(Clone)]
 pub struct Mission01State {
     pub player_x: i32,
     pub player_y: i32,
@@ -28,10 +36,25 @@ pub struct Mission01State {
     pub is_finished: bool,
 
     pub last_runtime: Option<Duration>,
+    pub best_runtime: Option<Duration>,
+
+    /// Path used to invoke the player's compiled firmware.
+    pub binary: String,
+    pub protocol: Protocol,
+    /// Long-running firmware, only used with `Protocol::Stdio`.
+    pub firmware: Option<FirmwareProcess>,
+    /// Set once the current build answered a live link query with a number.
+    pub link_confirmed: bool,
+    /// Last watchdog / crash report from the firmware, shown in the logs.
+    pub last_fault: Option<String>,
+    /// Panic line (or decoded signal) of the last crash, shown in the sidebar.
+    pub panic: Option<String>,
+    /// Hidden test suite results for the current build.
+    pub test_report: Option<SuiteReport>,
 }
 
-impl Mission01State {
-    pub fn new() -> Self {
+impl MissionState for Mission01State {
+    fn init(manifest: &MissionManifest) -> Self {
         let mut rng = rand::thread_rng();
 
         // HUGE MAP: 100x100 (10,000 tiles)
@@ -72,9 +95,81 @@ impl Mission01State {
             is_gps_compiled: false,
             is_finished: false,
             last_runtime: None,
+            best_runtime: None,
+            binary: artifacts::binary_path(manifest).display().to_string(),
+            protocol: manifest.protocol,
+            firmware: None,
+            link_confirmed: false,
+            last_fault: None,
+            panic: None,
+            test_report: None,
         }
     }
 
+    fn on_compile_start(&mut self) {
+        self.test_report = None;
+        self.last_fault = None;
+        self.panic = None;
+    }
+
+    fn test_suite(&self) -> TestSuite {
+        self.hidden_tests()
+    }
+
+    fn on_compile_success(&mut self, tests: Result<SuiteReport, RunError>) -> Result<(), String> {
+        suite::record(&mut self.test_report, tests)?;
+        self.is_gps_compiled = true;
+        // Replace any process still running the previous build.
+        self.link_confirmed = false;
+        self.firmware = match self.protocol {
+            Protocol::Stdio => Some(FirmwareProcess::new(&self.binary, Limits::persistent())),
+            Protocol::Args => None,
+        };
+        self.update_gps();
+        Ok(())
+    }
+
+    fn on_compile_fail(&mut self) {
+        self.is_gps_compiled = false;
+        self.firmware = None;
+    }
+
+    fn test_report(&self) -> Option<&SuiteReport> {
+        self.test_report.as_ref()
+    }
+
+    fn on_input(&mut self, key_code: KeyCode) {
+        match key_code {
+            KeyCode::Up => self.move_player(0, -1),
+            KeyCode::Down => self.move_player(0, 1),
+            KeyCode::Left => self.move_player(-1, 0),
+            KeyCode::Right => self.move_player(1, 0),
+            _ => {}
+        }
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect) {
+        ui::mission_01::render(f, self, area);
+    }
+
+    fn is_finished(&self) -> bool {
+        self.is_finished
+    }
+
+    fn best_latency(&self) -> Option<Duration> {
+        self.best_runtime
+    }
+
+    fn fault(&self) -> Option<&str> {
+        self.last_fault.as_deref()
+    }
+
+    fn footer_hints(&self) -> &'static str {
+        " [Arrows] Move | [C] Compile Code | [E] Edit | [TAB] View Logs "
+    }
+}
+
+impl Mission01State {
     pub fn move_player(&mut self, dx: i32, dy: i32) {
         if self.is_finished {
             return;
@@ -113,48 +208,115 @@ impl Mission01State {
     }
 
     fn run_gps_binary(&mut self, x1: i32, y1: i32, x2: i32, y2: i32) -> String {
-        let start_time = Instant::now();
-        let output = Command::new("./user_gps_bin")
-            .arg(x1.to_string())
-            .arg(y1.to_string())
-            .arg(x2.to_string())
-            .arg(y2.to_string())
-            .output();
-
-        self.last_runtime = Some(start_time.elapsed());
-
-        match output {
-            Ok(c) => {
-                if c.status.success() {
-                    let out = String::from_utf8_lossy(&c.stdout).to_string();
+        if let Some(firmware) = &mut self.firmware {
+            let start_time = Instant::now();
+            let reply = firmware.query(&format!("{} {} {} {}", x1, y1, x2, y2));
+            let elapsed = start_time.elapsed();
+
+            // Firmware from before the live link (e.g. kept by --init --upgrade)
+            // ignores --stdio: run it once per query instead.
+            let unsupported = !self.link_confirmed
+                && match &reply {
+                    Ok(out) => out.trim().parse::<f64>().is_err(),
+                    Err(QueryError::Crashed(report)) => !report.is_crash(),
+                    Err(QueryError::Fault(_)) => false,
+                };
+            if unsupported {
+                self.firmware = None;
+                return self.run_gps_binary(x1, y1, x2, y2);
+            }
+
+            self.last_runtime = Some(elapsed);
+            return match reply {
+                Ok(out) => {
+                    self.link_confirmed = true;
+                    self.last_fault = None;
+                    self.panic = None;
+                    if self.best_runtime.is_none_or(|best| elapsed < best) {
+                        self.best_runtime = Some(elapsed);
+                    }
                     format!("DIST: {}m", out.trim())
-                } else {
-                    "CRASH".to_string()
                 }
-            }
-            Err(_) => "EXEC_ERR".to_string(),
+                Err(e) => {
+                    self.last_fault = Some(e.to_string());
+                    self.panic = match &e {
+                        QueryError::Crashed(report) => Some(report.headline()),
+                        QueryError::Fault(_) => None,
+                    };
+                    e.headline()
+                }
+            };
         }
-    }
 
-    pub fn verify_integrity() -> Result<(), String> {
-        let output = Command::new("./user_gps_bin")
-            .arg("0")
-            .arg("0")
-            .arg("3")
-            .arg("4")
-            .output();
-        match output {
-            Ok(c) => {
-                if !c.status.success() {
-                    return Err("Runtime Error".to_string());
-                }
-                if String::from_utf8_lossy(&c.stdout).trim() == "5.00" {
-                    Ok(())
+        let args = [x1, y1, x2, y2].map(|v| v.to_string());
+        match runner::run(&self.binary, &args, &Limits::default()) {
+            Ok(out) => {
+                self.last_runtime = Some(out.elapsed);
+                if out.status.success() {
+                    self.last_fault = None;
+                    self.panic = None;
+                    if self.best_runtime.is_none_or(|best| out.elapsed < best) {
+                        self.best_runtime = Some(out.elapsed);
+                    }
+                    format!("DIST: {}m", out.stdout.trim())
                 } else {
-                    Err("Logic Error".to_string())
+                    let mut report = CrashReport::new(&out.status, &out.stderr);
+                    if report.panic.is_some() && !report.has_backtrace() {
+                        if let Ok(rerun) =
+                            runner::rerun_with_backtrace(&self.binary, &args, &Limits::default())
+                        {
+                            report.stderr = rerun.stderr.trim().to_string();
+                        }
+                    }
+                    let headline = report.headline();
+                    self.last_fault = Some(report.to_string());
+                    self.panic = Some(headline.clone());
+                    headline
                 }
             }
-            Err(e) => Err(format!("System Error: {}", e)),
+            Err(e) => {
+                let fault = e.to_string();
+                self.last_fault = Some(fault.clone());
+                self.panic = None;
+                fault
+            }
         }
     }
+
+    /// The hidden test suite: fixed edge cases plus random coordinates,
+    /// checked against `reference_distance`.
+    pub fn hidden_tests(&self) -> TestSuite {
+        let mut cases = vec![
+            TestCase::new(&["0", "0", "3", "4"], 5.0),
+            TestCase::new(&["5", "5", "5", "5"], 0.0),
+            TestCase::new(&["-3", "-4", "0", "0"], 5.0),
+            TestCase::new(&["10", "0", "-10", "0"], 20.0),
+            TestCase::new(&["0", "-7", "0", "7"], 14.0),
+            TestCase::new(&["1.5", "2.5", "4.5", "6.5"], 5.0),
+            TestCase::new(&["0", "0", "1000000", "1000000"], 1_000_000.0 * 2f64.sqrt()),
+            // Radiation bitflip: the firmware must not report NaN.
+            TestCase::new(&["NaN", "0", "0", "0"], 0.0),
+        ];
+        let mut rng = rand::thread_rng();
+        for _ in 0..suite::RANDOM_CASES {
+            let c: [i32; 4] = std::array::from_fn(|_| rng.gen_range(-500..=500));
+            let expected = reference_distance(c[0] as f64, c[1] as f64, c[2] as f64, c[3] as f64);
+            cases.push(TestCase::new(&c, expected));
+        }
+        TestSuite {
+            binary: self.binary.clone(),
+            cases,
+            tolerance: 0.01,
+        }
+    }
+}
+
+/// Reference implementation the hidden tests are checked against.
+fn reference_distance(x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
+    let distance = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+    if distance.is_nan() {
+        0.0
+    } else {
+        distance
+    }
 }
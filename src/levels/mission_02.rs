@@ -1,37 +1,88 @@
-use std::process::Command;
+use crate::artifacts;
+use crate::crash::CrashReport;
+use crate::gameplay::MissionState;
+use crate::manifest::MissionManifest;
+use crate::runner::{self, Limits, RunError};
+use crate::suite::{self, SuiteReport, TestCase, TestSuite};
+use crate::ui;
+use rand::Rng;
+use ratatui::{layout::Rect, Frame};
 
-#[derive(Clone)]
 pub struct Mission02State {
     pub turbidity: f32,
     pub ph: f32,
     pub output_log: String,
     pub is_compiled: bool,
     pub is_finished: bool,
+
+    /// Path used to invoke the player's compiled firmware.
+    pub binary: String,
+    /// Hidden test suite results for the current build.
+    pub test_report: Option<SuiteReport>,
 }
 
-impl Mission02State {
-    pub fn new() -> Self {
+impl MissionState for Mission02State {
+    fn init(manifest: &MissionManifest) -> Self {
         Mission02State {
             turbidity: 25.0, // Dirty water
             ph: 6.5,         // Slightly acidic
             output_log: "AWAITING FIRMWARE UPDATE...".to_string(),
             is_compiled: false,
             is_finished: false,
+            binary: artifacts::binary_path(manifest).display().to_string(),
+            test_report: None,
         }
     }
 
-    pub fn check_water(&mut self) {
+    fn on_compile_start(&mut self) {
+        self.test_report = None;
+    }
+
+    fn test_suite(&self) -> TestSuite {
+        self.hidden_tests()
+    }
+
+    fn on_compile_success(&mut self, tests: Result<SuiteReport, RunError>) -> Result<(), String> {
+        suite::record(&mut self.test_report, tests)?;
+        self.is_compiled = true;
+        self.check_water()
+    }
+
+    fn on_compile_fail(&mut self) {
+        self.is_compiled = false;
+    }
+
+    fn render(&self, f: &mut Frame, area: Rect) {
+        ui::mission_02::render(f, self, area);
+    }
+
+    fn is_finished(&self) -> bool {
+        self.is_finished
+    }
+
+    fn test_report(&self) -> Option<&SuiteReport> {
+        self.test_report.as_ref()
+    }
+}
+
+impl Mission02State {
+    /// Runs the firmware against the current sensor readings. Returns `Err` when
+    /// the watchdog had to step in, so the failure lands in the logs.
+    pub fn check_water(&mut self) -> Result<(), String> {
         if self.is_compiled {
             // We run the user's compiled binary with sensor data as arguments
-            // Usage: ./user_water_bin <turbidity> <ph>
-            let output = Command::new("./user_water_bin")
-                .arg(self.turbidity.to_string())
-                .arg(self.ph.to_string())
-                .output();
+            // Usage: user_water_bin <turbidity> <ph>
+            let args = [self.turbidity.to_string(), self.ph.to_string()];
+            let output = runner::run(&self.binary, &args, &Limits::default());
 
             match output {
+                Ok(c) if !c.status.success() => {
+                    let report = CrashReport::new(&c.status, &c.stderr);
+                    self.output_log = report.headline();
+                    return Err(report.to_string());
+                }
                 Ok(c) => {
-                    let out_str = String::from_utf8_lossy(&c.stdout).trim().to_string();
+                    let out_str = c.stdout.trim().to_string();
 
                     // Parse the user's output (expected: a float string like "4.50")
                     match out_str.parse::<f32>() {
@@ -57,9 +108,50 @@ impl Mission02State {
                         }
                     }
                 }
-                Err(_) => self.output_log = "HARDWARE FAILURE: PUMP CONNECTION LOST".to_string(),
+                Err(RunError::Spawn(_)) => {
+                    self.output_log = "HARDWARE FAILURE: PUMP CONNECTION LOST".to_string()
+                }
+                Err(e) => {
+                    self.output_log = e.to_string();
+                    return Err(self.output_log.clone());
+                }
             }
         }
+        Ok(())
+    }
+
+    /// The hidden test suite: fixed edge cases around the pH thresholds and
+    /// the safety clamp, plus random sensor readings.
+    fn hidden_tests(&self) -> TestSuite {
+        let mut readings = vec![
+            (25.0, 6.5),
+            (20.0, 7.0),
+            (0.0, 7.0),
+            (0.0, 0.0),
+            (5.0, 8.0),
+            (10.0, 7.1),
+            (30.0, 6.9),
+            (100.0, 14.0),
+        ];
+        let mut rng = rand::thread_rng();
+        for _ in 0..suite::RANDOM_CASES {
+            // One decimal place, like the real sensors report.
+            let turbidity = rng.gen_range(0..=1000) as f32 / 10.0;
+            let ph = rng.gen_range(0..=140) as f32 / 10.0;
+            readings.push((turbidity, ph));
+        }
+        let cases: Vec<TestCase> = readings
+            .into_iter()
+            .map(|(turbidity, ph)| {
+                let expected = self.calculate_correct_chlorine(turbidity, ph);
+                TestCase::new(&[turbidity, ph], expected as f64)
+            })
+            .collect();
+        TestSuite {
+            binary: self.binary.clone(),
+            cases,
+            tolerance: 0.01,
+        }
     }
 
     // This is the internal "Truth" logic to verify the user's code
@@ -1,2 +1,33 @@
 pub mod mission_01;
 pub mod mission_02;
+
+use crate::gameplay::MissionState;
+use crate::manifest::MissionManifest;
+use mission_01::Mission01State;
+use mission_02::Mission02State;
+
+type MissionInit = fn(&MissionManifest) -> Box<dyn MissionState>;
+
+fn boxed<M: MissionState + 'static>(manifest: &MissionManifest) -> Box<dyn MissionState> {
+    Box::new(M::init(manifest))
+}
+
+/// Gameplay implementations a manifest can refer to through its `logic` key.
+pub const LOGIC: &[(&str, MissionInit)] = &[
+    ("fog_navigator", boxed::<Mission01State>),
+    ("toxic_flow", boxed::<Mission02State>),
+];
+
+pub fn is_known_logic(logic: &str) -> bool {
+    LOGIC.iter().any(|(name, _)| *name == logic)
+}
+
+/// Builds the gameplay state for a mission. Manifests are validated on load,
+/// so an unknown `logic` key here is a bug.
+pub fn create(manifest: &MissionManifest) -> Box<dyn MissionState> {
+    let (_, init) = LOGIC
+        .iter()
+        .find(|(name, _)| *name == manifest.logic)
+        .expect("mission logic validated by manifest::load_catalog");
+    init(manifest)
+}
@@ -1,14 +1,35 @@
 mod app;
+mod artifacts;
+mod check;
+mod compiler;
+mod crash;
+mod diagnostics;
+mod editor;
+mod firmware;
 mod gameplay;
+mod harness;
+mod job;
 mod tui;
 mod ui;
+mod watch;
 mod levels;
+mod manifest;
+mod pty;
+mod rules;
+mod runner;
+mod sandbox;
+mod save;
 mod setup; 
+mod suite;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use app::{App, CurrentScreen};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use crossterm::event::{self, Event};
+use std::path::PathBuf;
+
+/// Environment fallback for `--workspace`.
+const WORKSPACE_ENV: &str = "RUST_SURVIVAL_WORKSPACE";
 
 // Define CLI arguments
 #[derive(Parser, Debug)]
@@ -17,34 +38,167 @@ struct Args {
     /// Initialize the mission files in the current directory
     #[arg(long)]
     init: bool,
+
+    /// With --init: add newly shipped missions to an existing workspace and
+    /// update files you haven't edited. Your code is never overwritten
+    #[arg(long, requires = "init")]
+    upgrade: bool,
+
+    /// Run player firmware isolated: scratch directory, empty environment,
+    /// no network and a read-only workspace (Linux namespaces when available)
+    #[arg(long, global = true)]
+    sandbox: bool,
+
+    /// Banned constructs (e.g. .unwrap()) fail the mission instead of being reported
+    #[arg(long, global = true)]
+    hardcore: bool,
+
+    /// Directory holding missions/, the save file and compiled firmware.
+    /// Defaults to $RUST_SURVIVAL_WORKSPACE, then the current directory
+    #[arg(long, value_name = "DIR", global = true)]
+    workspace: Option<PathBuf>,
+
+    /// Delete all compiled firmware from the build directory
+    #[arg(long)]
+    clean: bool,
+
+    /// Restore a mission file to its original version, keeping a timestamped
+    /// backup of your current code next to it
+    #[arg(long, value_name = "MISSION")]
+    reset: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Compile and verify a mission without the TUI. Exits non-zero on failure
+    Check {
+        /// Mission id (`1`), source file stem (`01_shelter`) or logic name
+        mission: String,
+
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
+    // Everything below (missions/, build dir, save file, firmware runs, the
+    // embedded shell) uses paths relative to the workspace, so move into it.
+    if let Some(dir) = workspace_dir(&args) {
+        if args.init {
+            std::fs::create_dir_all(&dir)
+                .with_context(|| format!("Failed to create workspace {}", dir.display()))?;
+        }
+        if let Err(e) = std::env::set_current_dir(&dir) {
+            eprintln!("ERROR: Cannot open workspace {}: {}", dir.display(), e);
+            eprintln!("Run 'rust_survival --init --workspace {}' to create it.", dir.display());
+            std::process::exit(2);
+        }
+    }
+
     // 1. Handle the initialization command
     if args.init {
-        setup::initialize_workspace()?;
+        setup::initialize_workspace(args.upgrade)?;
+        return Ok(());
+    }
+
+    if args.clean {
+        if artifacts::clean()? {
+            println!("✔ Removed '{}/'.", artifacts::BUILD_DIR);
+        } else {
+            println!("Nothing to clean.");
+        }
         return Ok(());
     }
 
     // 2. Safety Check: Ensure missions exist
     if !std::path::Path::new("missions").exists() {
         eprintln!("ERROR: Critical Mission Files Missing.");
+        if let Ok(dir) = std::env::current_dir() {
+            eprintln!("No 'missions' folder in workspace {}.", dir.display());
+        }
         eprintln!("Run 'rust_survival --init' to generate the workspace.");
+        std::process::exit(2);
+    }
+
+    let missions = match manifest::load_catalog(std::path::Path::new("missions")) {
+        Ok(missions) if !missions.is_empty() => missions,
+        Ok(_) => {
+            eprintln!("ERROR: No mission manifests found in 'missions/'.");
+            eprintln!("Each mission needs a .toml manifest next to its source file.");
+            eprintln!("Workspace from an older version? Run --init --upgrade to add them.");
+            std::process::exit(2);
+        }
+        Err(e) => {
+            eprintln!("ERROR: Mission catalog corrupted.");
+            eprintln!("{:#}", e);
+            std::process::exit(2);
+        }
+    };
+
+    if let Some(query) = &args.reset {
+        let manifest = find_mission(&missions, query);
+        match setup::reset_mission(&manifest.source)? {
+            Some(backup) => println!("✔ Backed up your code to '{}'.", backup.display()),
+            None => println!("No existing file to back up."),
+        }
+        println!("✔ Restored '{}'.", manifest.source.display());
         return Ok(());
     }
 
+    if args.sandbox {
+        if let sandbox::Isolation::Fallback(reason) = sandbox::enable(std::path::Path::new(".")) {
+            eprintln!("WARNING: Sandbox degraded: {}.", reason);
+        }
+    }
+    if args.hardcore {
+        rules::enable_hardcore();
+    }
+
+    if let Some(Commands::Check { mission, json }) = &args.command {
+        let manifest = find_mission(&missions, mission);
+        let passed = check::run(manifest, *json);
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
+    let save_path = std::path::PathBuf::from(save::SAVE_FILE);
+    let save_data = match save::SaveData::load(&save_path) {
+        Ok(save_data) => save_data,
+        Err(e) => {
+            eprintln!("ERROR: Campaign archive unreadable.");
+            eprintln!("{:#}", e);
+            eprintln!(
+                "Move or delete '{}' to start a new campaign.",
+                save::SAVE_FILE
+            );
+            std::process::exit(2);
+        }
+    };
+
     // 3. Start the Game Loop
     let mut terminal = tui::init()?;
-    let mut app = App::new();
+    let mut app = App::new(missions, save_data, save_path);
 
     loop {
+        app.tick();
         terminal.draw(|f| ui::ui(f, &app))?;
         
         if event::poll(std::time::Duration::from_millis(16))? {
             if let Event::Key(key) = event::read()? {
-                app.handle_input(key.code);
+                app.handle_key(key);
+                if app.edit_requested {
+                    app.edit_requested = false;
+                    tui::restore()?;
+                    let line = app.first_diagnostic_line();
+                    let result = editor::open(&app.active_mission.path, line);
+                    terminal = tui::init()?;
+                    app.finish_edit(result);
+                }
                 if let CurrentScreen::Exiting = app.current_screen {
                     break;
                 }
@@ -55,3 +209,29 @@ fn main() -> Result<()> {
     tui::restore()?;
     Ok(())
 }
+
+/// Looks up a mission named on the command line, or lists the valid ones and
+/// exits with status 2.
+fn find_mission<'a>(
+    missions: &'a [manifest::MissionManifest],
+    query: &str,
+) -> &'a manifest::MissionManifest {
+    let Some(manifest) = check::find(missions, query) else {
+        eprintln!("ERROR: Unknown mission '{}'.", query);
+        eprintln!("Available missions:");
+        for m in missions {
+            eprintln!("  {:02}  {}", m.id, m.title);
+        }
+        std::process::exit(2);
+    };
+    manifest
+}
+
+/// `--workspace`, else `$RUST_SURVIVAL_WORKSPACE`. `None` means the current directory.
+fn workspace_dir(args: &Args) -> Option<PathBuf> {
+    args.workspace.clone().or_else(|| {
+        std::env::var_os(WORKSPACE_ENV)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+    })
+}
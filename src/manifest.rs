@@ -0,0 +1,189 @@
+use crate::levels;
+use crate::rules::Rule;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Mission metadata loaded from a `*.toml` file next to the mission source.
+///
+/// ```toml
+/// id = 1
+/// title = "FOG NAVIGATOR"
+/// description = "Repair GPS to find shelter."
+/// source = "01_shelter.rs"
+/// binary = "user_gps_bin"
+/// logic = "fog_navigator"
+/// concepts = ["f64 math", "CLI arguments"]
+/// difficulty = 1
+/// requires = []
+/// protocol = "stdio"
+/// banned = ["unwrap", "expect", "panic!", "unsafe", "static mut"]
+///
+/// [rustc]
+/// edition = "2021"
+/// panic = "abort"
+/// deny_warnings = true
+/// ```
+///
+/// Missions too big for one file can be Cargo projects instead. `source` is
+/// then the file the game opens for editing, inside the project:
+///
+/// ```toml
+/// kind = "cargo"
+/// project = "03_radio"
+/// source = "03_radio/src/main.rs"
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct MissionManifest {
+    pub id: u32,
+    pub title: String,
+    pub description: String,
+    /// Source file, relative to the manifest. Resolved to a full path on load.
+    pub source: PathBuf,
+    /// Name of the binary `rustc` produces for this mission.
+    pub binary: String,
+    /// Which gameplay implementation drives the mission (see `levels::LOGIC`).
+    pub logic: String,
+    #[serde(default)]
+    pub concepts: Vec<String>,
+    #[serde(default = "default_difficulty")]
+    pub difficulty: u8,
+    /// Ids of missions that must be completed first.
+    #[serde(default)]
+    pub requires: Vec<u32>,
+    /// How the game talks to the compiled firmware.
+    #[serde(default)]
+    pub protocol: Protocol,
+    /// How the firmware is built.
+    #[serde(default)]
+    pub kind: MissionKind,
+    /// Directory holding `Cargo.toml`, relative to the manifest. Required for
+    /// `kind = "cargo"`; resolved to a full path on load.
+    #[serde(default)]
+    pub project: Option<PathBuf>,
+    /// Compiler settings and lint gates the firmware must pass.
+    #[serde(default)]
+    pub rustc: RustcFlags,
+    /// Constructs the player's code must not use (see `rules::scan`). Hits are
+    /// reported, and fail the mission in hardcore mode.
+    #[serde(default)]
+    pub banned: Vec<Rule>,
+}
+
+/// Extra `rustc` settings a mission can demand, from its `[rustc]` table.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct RustcFlags {
+    /// `--edition`. Cargo missions set this in their `Cargo.toml` instead.
+    pub edition: Option<String>,
+    /// `-C opt-level`: "0" to "3", "s" or "z".
+    pub opt_level: Option<String>,
+    /// `-C panic`: "abort" or "unwind".
+    pub panic: Option<String>,
+    /// `-D warnings`: any warning fails the build.
+    #[serde(default)]
+    pub deny_warnings: bool,
+    /// `-F unsafe_code`: `unsafe` is rejected and can't be re-allowed in code.
+    #[serde(default)]
+    pub forbid_unsafe: bool,
+}
+
+impl RustcFlags {
+    /// Every flag except `--edition`, which cargo passes itself.
+    pub fn codegen_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(level) = &self.opt_level {
+            args.push(format!("-Copt-level={}", level));
+        }
+        if let Some(strategy) = &self.panic {
+            args.push(format!("-Cpanic={}", strategy));
+        }
+        if self.deny_warnings {
+            args.extend(["-D".to_string(), "warnings".to_string()]);
+        }
+        if self.forbid_unsafe {
+            args.extend(["-F".to_string(), "unsafe_code".to_string()]);
+        }
+        args
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MissionKind {
+    /// A single `.rs` file compiled with bare `rustc`.
+    #[default]
+    Rustc,
+    /// A Cargo project built with `cargo build --offline`, so the player can
+    /// split code into modules and write unit tests.
+    Cargo,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    /// One process per query, inputs passed as command line arguments.
+    #[default]
+    Args,
+    /// One long-running process, one query per line on stdin (see `firmware::FirmwareProcess`).
+    Stdio,
+}
+
+fn default_difficulty() -> u8 {
+    1
+}
+
+/// Reads every manifest in `dir`, sorted by mission id.
+pub fn load_catalog(dir: &Path) -> Result<Vec<MissionManifest>> {
+    let mut catalog = Vec::new();
+
+    let entries = fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+            continue;
+        }
+
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut manifest: MissionManifest = toml::from_str(&raw)
+            .with_context(|| format!("Invalid mission manifest {}", path.display()))?;
+
+        if !levels::is_known_logic(&manifest.logic) {
+            bail!(
+                "Unknown mission logic '{}' in {}",
+                manifest.logic,
+                path.display()
+            );
+        }
+        manifest.source = dir.join(&manifest.source);
+        manifest.project = manifest.project.map(|project| dir.join(project));
+        if manifest.kind == MissionKind::Cargo && manifest.project.is_none() {
+            bail!(
+                "Cargo mission in {} needs a 'project' directory",
+                path.display()
+            );
+        }
+        catalog.push(manifest);
+    }
+
+    catalog.sort_by_key(|m| m.id);
+    for pair in catalog.windows(2) {
+        if pair[0].id == pair[1].id {
+            bail!("Duplicate mission id {:02}", pair[0].id);
+        }
+    }
+    for mission in &catalog {
+        for required in &mission.requires {
+            if !catalog.iter().any(|m| m.id == *required) {
+                bail!(
+                    "Mission {:02} requires unknown mission {:02}",
+                    mission.id,
+                    required
+                );
+            }
+        }
+    }
+
+    Ok(catalog)
+}
@@ -0,0 +1,246 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::cell::Cell;
+use std::env;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use tui_term::vt100::Parser;
+
+/// Lines of history kept above the visible screen.
+const SCROLLBACK: usize = 1000;
+
+/// A shell running on a pseudo-terminal, for the TERMINAL tab. Output is fed
+/// into a `vt100` parser on a background thread; the UI renders its screen.
+pub struct Pty {
+    parser: Arc<Mutex<Parser>>,
+    master: File,
+    child: Child,
+    /// Current `(rows, cols)`. Updated from the render pass, hence the `Cell`.
+    size: Cell<(u16, u16)>,
+}
+
+impl Pty {
+    /// Starts `$SHELL` (or `/bin/sh`) in the working directory.
+    pub fn spawn(rows: u16, cols: u16) -> io::Result<Self> {
+        let (master, slave) = sys::open(rows, cols)?;
+        let shell = env::var("SHELL")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "/bin/sh".to_string());
+
+        let mut command = Command::new(shell);
+        command
+            .env("TERM", "xterm-256color")
+            .stdin(Stdio::from(slave.try_clone()?))
+            .stdout(Stdio::from(slave.try_clone()?))
+            .stderr(Stdio::from(slave));
+        sys::make_controlling_tty(&mut command);
+        let child = command.spawn()?;
+        // Close our copies of the slave so reads hit EOF once the shell exits.
+        drop(command);
+
+        let parser = Arc::new(Mutex::new(Parser::new(rows, cols, SCROLLBACK)));
+        let output = Arc::clone(&parser);
+        let mut reader = master.try_clone()?;
+        thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            loop {
+                match reader.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => output
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .process(&buf[..n]),
+                }
+            }
+        });
+
+        Ok(Self {
+            parser,
+            master,
+            child,
+            size: Cell::new((rows, cols)),
+        })
+    }
+
+    /// The emulated screen, locked for rendering.
+    pub fn screen(&self) -> MutexGuard<'_, Parser> {
+        self.parser.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Matches the terminal size to the area it is drawn in.
+    pub fn resize(&self, rows: u16, cols: u16) {
+        if self.size.get() == (rows, cols) || rows == 0 || cols == 0 {
+            return;
+        }
+        self.size.set((rows, cols));
+        self.screen().set_size(rows, cols);
+        sys::set_size(&self.master, rows, cols);
+    }
+
+    /// Forwards a key press to the shell.
+    pub fn send_key(&mut self, key: KeyEvent) {
+        let application_cursor = self.screen().screen().application_cursor();
+        if let Some(bytes) = encode_key(key, application_cursor) {
+            let _ = self.master.write_all(&bytes);
+        }
+    }
+
+    /// `Some` once the shell has exited.
+    pub fn try_wait(&mut self) -> Option<ExitStatus> {
+        self.child.try_wait().ok().flatten()
+    }
+}
+
+impl Drop for Pty {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Translates a key press into the bytes a VT100-style terminal would send.
+fn encode_key(key: KeyEvent, application_cursor: bool) -> Option<Vec<u8>> {
+    let arrow = |c: u8| {
+        if application_cursor {
+            vec![0x1b, b'O', c]
+        } else {
+            vec![0x1b, b'[', c]
+        }
+    };
+    let bytes = match key.code {
+        KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            match c.to_ascii_lowercase() {
+                c @ 'a'..='z' => vec![c as u8 - b'a' + 1],
+                ' ' | '@' => vec![0],
+                '[' => vec![0x1b],
+                '\\' => vec![0x1c],
+                ']' => vec![0x1d],
+                _ => return None,
+            }
+        }
+        KeyCode::Char(c) => {
+            let mut bytes = Vec::new();
+            if key.modifiers.contains(KeyModifiers::ALT) {
+                bytes.push(0x1b);
+            }
+            bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+            bytes
+        }
+        KeyCode::Enter => vec![b'\r'],
+        KeyCode::Backspace => vec![0x7f],
+        KeyCode::Tab => vec![b'\t'],
+        KeyCode::BackTab => b"\x1b[Z".to_vec(),
+        KeyCode::Esc => vec![0x1b],
+        KeyCode::Up => arrow(b'A'),
+        KeyCode::Down => arrow(b'B'),
+        KeyCode::Right => arrow(b'C'),
+        KeyCode::Left => arrow(b'D'),
+        KeyCode::Home => b"\x1b[H".to_vec(),
+        KeyCode::End => b"\x1b[F".to_vec(),
+        KeyCode::PageUp => b"\x1b[5~".to_vec(),
+        KeyCode::PageDown => b"\x1b[6~".to_vec(),
+        KeyCode::Insert => b"\x1b[2~".to_vec(),
+        KeyCode::Delete => b"\x1b[3~".to_vec(),
+        KeyCode::F(n @ 1..=4) => vec![0x1b, b'O', b'P' + n - 1],
+        KeyCode::F(n) => {
+            let code = match n {
+                5 => 15,
+                6..=10 => n + 11,
+                11..=12 => n + 12,
+                _ => return None,
+            };
+            format!("\x1b[{}~", code).into_bytes()
+        }
+        _ => return None,
+    };
+    Some(bytes)
+}
+
+#[cfg(unix)]
+mod sys {
+    use std::fs::File;
+    use std::io;
+    use std::os::fd::{FromRawFd, RawFd};
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::process::CommandExt;
+    use std::process::Command;
+    use std::ptr;
+
+    /// Opens a pty pair sized `rows` x `cols`. Returns `(master, slave)`.
+    pub fn open(rows: u16, cols: u16) -> io::Result<(File, File)> {
+        let mut master: RawFd = -1;
+        let mut slave: RawFd = -1;
+        let size = winsize(rows, cols);
+        // SAFETY: both out-pointers are valid; the name buffer and termios are optional.
+        let ret =
+            unsafe { libc::openpty(&mut master, &mut slave, ptr::null_mut(), ptr::null(), &size) };
+        if ret != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: openpty succeeded, so both descriptors are open and owned by us.
+        let (master, slave) = unsafe { (File::from_raw_fd(master), File::from_raw_fd(slave)) };
+        // Keep the master out of the shell and anything else we spawn.
+        for file in [&master, &slave] {
+            // SAFETY: plain fcntl on a descriptor we own.
+            if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok((master, slave))
+    }
+
+    /// Puts the child in its own session with the pty as controlling
+    /// terminal, so job control and Ctrl-C work.
+    pub fn make_controlling_tty(command: &mut Command) {
+        // SAFETY: only async-signal-safe calls run between fork and exec.
+        unsafe {
+            command.pre_exec(|| {
+                if libc::setsid() < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                if libc::ioctl(0, libc::TIOCSCTTY as _, 0) < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+
+    pub fn set_size(master: &File, rows: u16, cols: u16) {
+        let size = winsize(rows, cols);
+        // SAFETY: TIOCSWINSZ reads a winsize from a valid pointer.
+        unsafe {
+            libc::ioctl(master.as_raw_fd(), libc::TIOCSWINSZ, &size);
+        }
+    }
+
+    fn winsize(rows: u16, cols: u16) -> libc::winsize {
+        libc::winsize {
+            ws_row: rows,
+            ws_col: cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod sys {
+    use std::fs::File;
+    use std::io;
+    use std::process::Command;
+
+    pub fn open(_rows: u16, _cols: u16) -> io::Result<(File, File)> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the embedded terminal needs a Unix pty",
+        ))
+    }
+
+    pub fn make_controlling_tty(_command: &mut Command) {}
+
+    pub fn set_size(_master: &File, _rows: u16, _cols: u16) {}
+}
@@ -0,0 +1,394 @@
+use crate::harness;
+use serde::Deserialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static HARDCORE: AtomicBool = AtomicBool::new(false);
+
+/// Makes banned constructs fail the mission instead of only being reported.
+pub fn enable_hardcore() {
+    HARDCORE.store(true, Ordering::Relaxed);
+}
+
+pub fn hardcore() -> bool {
+    HARDCORE.load(Ordering::Relaxed)
+}
+
+/// A construct a mission can ban from the player's firmware, named in the
+/// manifest's `banned` list exactly as written in code.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum Rule {
+    #[serde(rename = "unwrap")]
+    Unwrap,
+    #[serde(rename = "expect")]
+    Expect,
+    #[serde(rename = "panic!")]
+    Panic,
+    #[serde(rename = "unsafe")]
+    Unsafe,
+    #[serde(rename = "static mut")]
+    StaticMut,
+}
+
+impl Rule {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Rule::Unwrap => ".unwrap()",
+            Rule::Expect => ".expect()",
+            Rule::Panic => "panic!",
+            Rule::Unsafe => "unsafe",
+            Rule::StaticMut => "static mut",
+        }
+    }
+}
+
+/// One banned construct found in the player's code.
+pub struct Violation {
+    pub rule: Rule,
+    /// File the construct was found in.
+    pub file: PathBuf,
+    /// 1-based line in `file`.
+    pub line: usize,
+    /// The offending line, trimmed.
+    pub text: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: `{}` is banned | {}",
+            self.file.display(),
+            self.line,
+            self.rule.label(),
+            self.text
+        )
+    }
+}
+
+/// Finds every use of a `banned` construct in `source` (read from `file`).
+/// Comments and string literals are ignored. With `skip_harness` the
+/// protected regions are skipped too; only pass it for a file whose harness
+/// was checked against the shipped original, or anyone could hide code behind
+/// a forged marker.
+pub fn scan(file: &Path, source: &str, banned: &[Rule], skip_harness: bool) -> Vec<Violation> {
+    if banned.is_empty() {
+        return Vec::new();
+    }
+    let protected = if skip_harness {
+        harness::protected_ranges(source)
+    } else {
+        Vec::new()
+    };
+    let code = strip_comments_and_literals(source);
+    let original: Vec<&str> = source.lines().collect();
+
+    let mut violations = Vec::new();
+    for (i, line) in code.lines().enumerate() {
+        if protected.iter().any(|range| range.contains(&i)) {
+            continue;
+        }
+        let words = words(line);
+        for (w, (word, start)) in words.iter().enumerate() {
+            let before = line[..*start].trim_end();
+            let after = line[start + word.len()..].trim_start();
+            let rule = match *word {
+                "unwrap" if before.ends_with('.') || before.ends_with("::") => Rule::Unwrap,
+                "expect" if before.ends_with('.') || before.ends_with("::") => Rule::Expect,
+                "panic" if after.starts_with('!') => Rule::Panic,
+                "unsafe" => Rule::Unsafe,
+                "static" if words.get(w + 1).is_some_and(|(next, _)| *next == "mut") => {
+                    Rule::StaticMut
+                }
+                _ => continue,
+            };
+            if banned.contains(&rule) {
+                violations.push(Violation {
+                    rule,
+                    file: file.to_path_buf(),
+                    line: i + 1,
+                    text: original.get(i).map_or("", |l| l.trim()).to_string(),
+                });
+            }
+        }
+    }
+    violations
+}
+
+/// Identifiers in `line` with their byte offsets.
+fn words(line: &str) -> Vec<(&str, usize)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        let ident = is_ident(c);
+        match (start, ident) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                words.push((&line[s..i], s));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+/// Replaces comments and the contents of string and char literals with
+/// spaces, keeping line breaks so line numbers still match.
+pub fn strip_comments_and_literals(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::with_capacity(source.len());
+    let blank = |c: char| if c == '\n' { '\n' } else { ' ' };
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                out.push(' ');
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            let mut depth = 0;
+            while i < chars.len() {
+                let pair = (chars[i], chars.get(i + 1).copied());
+                if pair == ('/', Some('*')) {
+                    depth += 1;
+                } else if pair == ('*', Some('/')) {
+                    depth -= 1;
+                }
+                if matches!(pair, ('/', Some('*')) | ('*', Some('/'))) {
+                    out.push_str("  ");
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    out.push(blank(chars[i]));
+                    i += 1;
+                }
+            }
+        } else if let Some((hashes, quote)) = raw_string_at(&chars, i) {
+            // Raw string: r"...", r#"..."#, br"..." or cr"...".
+            let closing = format!("\"{}", "#".repeat(hashes));
+            let rest: String = chars[quote + 1..].iter().collect();
+            let len = rest.find(&closing).map_or(chars.len() - quote - 1, |end| {
+                rest[..end].chars().count() + closing.len()
+            });
+            let end = (quote + 1 + len).min(chars.len());
+            out.extend(chars[i..end].iter().map(|&ch| blank(ch)));
+            i = end;
+        } else if (c == 'b' && matches!(next, Some('"') | Some('\''))
+            || c == 'c' && next == Some('"'))
+            && !(i > 0 && is_ident(chars[i - 1]))
+        {
+            // Byte string, C string or byte char: blank the prefix, the literal
+            // itself is handled like any other on the next pass.
+            out.push(' ');
+            i += 1;
+        } else if c == '"' {
+            out.push(' ');
+            i += 1;
+            while i < chars.len() {
+                match chars[i] {
+                    '\\' => {
+                        out.push(' ');
+                        if let Some(&escaped) = chars.get(i + 1) {
+                            out.push(blank(escaped));
+                        }
+                        i += 2;
+                    }
+                    '"' => {
+                        out.push(' ');
+                        i += 1;
+                        break;
+                    }
+                    other => {
+                        out.push(blank(other));
+                        i += 1;
+                    }
+                }
+            }
+        } else if c == '\'' {
+            // Char literal ('x', '\n'); a lone quote is a lifetime and kept.
+            let end = match next {
+                // Skip the escaped character itself so '\'' closes correctly.
+                Some('\\') => chars
+                    .get(i + 3..)
+                    .and_then(|rest| rest.iter().position(|&ch| ch == '\''))
+                    .map(|p| i + 3 + p),
+                Some(_) if chars.get(i + 2) == Some(&'\'') => Some(i + 2),
+                _ => None,
+            };
+            match end {
+                Some(end) => {
+                    out.push_str(&" ".repeat(end - i + 1));
+                    i = end + 1;
+                }
+                None => {
+                    out.push(c);
+                    i += 1;
+                }
+            }
+        } else {
+            out.push(c);
+            i += 1;
+        }
+    }
+    out
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// For a raw string literal (`r"`, `r#"`, `br"`, `cr#"`) starting at `i`:
+/// the number of `#`s and the index of the opening quote.
+fn raw_string_at(chars: &[char], i: usize) -> Option<(usize, usize)> {
+    if i > 0 && is_ident(chars[i - 1]) {
+        return None;
+    }
+    let r = match chars[i] {
+        'r' => i,
+        'b' | 'c' if chars.get(i + 1) == Some(&'r') => i + 1,
+        _ => return None,
+    };
+    let hashes = chars[r + 1..].iter().take_while(|&&c| c == '#').count();
+    let quote = r + 1 + hashes;
+    (chars.get(quote) == Some(&'"')).then_some((hashes, quote))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Rule; 5] = [
+        Rule::Unwrap,
+        Rule::Expect,
+        Rule::Panic,
+        Rule::Unsafe,
+        Rule::StaticMut,
+    ];
+
+    fn hits(source: &str) -> Vec<(Rule, usize)> {
+        scan(Path::new("main.rs"), source, &ALL, true)
+            .into_iter()
+            .map(|v| (v.rule, v.line))
+            .collect()
+    }
+
+    #[test]
+    fn finds_each_banned_construct() {
+        let source = "fn main() {\n\
+                      let a = x.unwrap();\n\
+                      let b = y.expect(\"b\");\n\
+                      panic!(\"boom\");\n\
+                      unsafe { go() }\n\
+                      }\n\
+                      static mut COUNT: u32 = 0;\n";
+        assert_eq!(
+            hits(source),
+            vec![
+                (Rule::Unwrap, 2),
+                (Rule::Expect, 3),
+                (Rule::Panic, 4),
+                (Rule::Unsafe, 5),
+                (Rule::StaticMut, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_comments_and_strings() {
+        let source = "// x.unwrap()\n\
+                      /* panic!() /* nested */ unsafe */\n\
+                      let s = \"x.unwrap() \\\" panic!()\";\n\
+                      let r = r#\"a \" x.expect(\"\") \"#;\n\
+                      static COUNT: u32 = 0;\n\
+                      let unwrap_or = f.unwrap_or(0);\n";
+        assert!(hits(source).is_empty());
+    }
+
+    #[test]
+    fn byte_and_c_raw_strings_end_at_the_first_quote() {
+        // A backslash does not escape the quote in a raw string, so the
+        // call after it is real code.
+        assert_eq!(
+            hits("let b = br\"\\\"; x.unwrap(); //\";"),
+            vec![(Rule::Unwrap, 1)]
+        );
+        assert_eq!(
+            hits("let c = cr\"\\\"; x.unwrap(); //\";"),
+            vec![(Rule::Unwrap, 1)]
+        );
+        assert!(hits("let b = br#\"x.unwrap()\"#;").is_empty());
+    }
+
+    #[test]
+    fn byte_and_c_strings_are_literals() {
+        assert!(hits("let b = b\"x.unwrap()\";").is_empty());
+        assert!(hits("let c = c\"panic!()\";").is_empty());
+        assert!(hits("let q = b'\\''; let s = \"x.unwrap()\";").is_empty());
+    }
+
+    #[test]
+    fn escaped_quote_char_literal_closes() {
+        assert!(hits("let q = '\\''; let s = \"x.unwrap()\";").is_empty());
+        assert_eq!(hits("let q = '\\''; x.unwrap();"), vec![(Rule::Unwrap, 1)]);
+        assert!(hits("let q = '\"'; let s = \"x.unwrap()\";").is_empty());
+    }
+
+    #[test]
+    fn lifetimes_and_labels_are_code() {
+        let source = "fn f<'a>(x: &'a Option<u8>) -> u8 {\n\
+                      'outer: loop { break 'outer; }\n\
+                      x.unwrap()\n\
+                      }\n";
+        assert_eq!(hits(source), vec![(Rule::Unwrap, 3)]);
+    }
+
+    #[test]
+    fn identifiers_ending_in_a_prefix_are_not_literals() {
+        // `bar` and `abc` end in `r`/`c`, not raw or C string prefixes.
+        assert_eq!(
+            hits("let x = bar(\"\"); abc(\"\"); y.unwrap();"),
+            vec![(Rule::Unwrap, 1)]
+        );
+    }
+
+    #[test]
+    fn harness_is_skipped_only_when_asked() {
+        let source = format!(
+            "// {}\nfn main() {{ x.unwrap(); }}\n// {}\nfn f() {{ y.unwrap(); }}\n",
+            harness::BEGIN_MARKER,
+            harness::END_MARKER
+        );
+        assert_eq!(hits(&source), vec![(Rule::Unwrap, 4)]);
+
+        let full: Vec<usize> = scan(Path::new("forged.rs"), &source, &ALL, false)
+            .into_iter()
+            .map(|v| v.line)
+            .collect();
+        assert_eq!(full, vec![2, 4]);
+    }
+
+    #[test]
+    fn only_banned_rules_are_reported() {
+        let source = "x.unwrap();\npanic!();\n";
+        let found = scan(Path::new("main.rs"), source, &[Rule::Panic], false);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].rule, Rule::Panic);
+        assert_eq!(found[0].text, "panic!();");
+        assert!(scan(Path::new("main.rs"), source, &[], false).is_empty());
+    }
+
+    #[test]
+    fn stripping_keeps_line_numbers() {
+        let source = "let s = \"a\nb\";\n/* c\nd */ x\n";
+        let stripped = strip_comments_and_literals(source);
+        assert_eq!(stripped.lines().count(), source.lines().count());
+        assert_eq!(stripped.lines().nth(3).map(str::trim), Some("x"));
+    }
+}
@@ -0,0 +1,250 @@
+use crate::sandbox;
+use std::fmt;
+use std::io::{self, Read};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Resource budget for one run of player firmware.
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    /// Wall-clock time before the watchdog kills the process.
+    pub wall_time: Duration,
+    /// CPU seconds (`RLIMIT_CPU`). `None` for long-running processes, where
+    /// CPU time accumulates across queries.
+    pub cpu_seconds: Option<u64>,
+    /// Address space limit in bytes (`RLIMIT_AS`).
+    pub memory_bytes: Option<u64>,
+    /// Bytes of stdout/stderr kept per stream. Anything beyond kills the process.
+    pub max_output: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            wall_time: Duration::from_millis(500),
+            cpu_seconds: Some(1),
+            memory_bytes: Some(256 * 1024 * 1024),
+            max_output: 64 * 1024,
+        }
+    }
+}
+
+impl Limits {
+    /// Budget for a firmware kept alive across queries: `wall_time` applies per
+    /// reply, and there is no CPU cap since CPU time adds up over the session.
+    pub fn persistent() -> Self {
+        Self {
+            cpu_seconds: None,
+            ..Self::default()
+        }
+    }
+}
+
+/// Output of a firmware run that finished within its limits.
+pub struct RunOutput {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+    pub elapsed: Duration,
+}
+
+/// Why the runner gave up on a firmware run.
+#[derive(Debug)]
+pub enum RunError {
+    /// The binary could not be started at all.
+    Spawn(io::Error),
+    /// Still running when the wall-clock budget ran out.
+    Timeout(Duration),
+    /// Wrote more than `max_output` bytes to stdout or stderr.
+    OutputLimit(usize),
+    /// Killed by the kernel for exceeding `RLIMIT_CPU`.
+    CpuLimit(u64),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Spawn(e) => write!(f, "EXEC FAILURE: could not start firmware ({})", e),
+            RunError::Timeout(limit) => write!(
+                f,
+                "WATCHDOG RESET: firmware exceeded {}ms",
+                limit.as_millis()
+            ),
+            RunError::OutputLimit(limit) => write!(
+                f,
+                "WATCHDOG RESET: firmware flooded the bus (over {} bytes of output)",
+                limit
+            ),
+            RunError::CpuLimit(secs) => {
+                write!(f, "WATCHDOG RESET: firmware exceeded {}s of CPU time", secs)
+            }
+        }
+    }
+}
+
+/// Runs `binary args...` to completion under `limits`, killing it if it misbehaves.
+pub fn run(binary: &str, args: &[String], limits: &Limits) -> Result<RunOutput, RunError> {
+    execute(binary, args, limits, false)
+}
+
+/// Same as `run`, with `RUST_BACKTRACE=1` so a panic prints its full trace.
+/// Used to investigate a crash after the fact.
+pub fn rerun_with_backtrace(
+    binary: &str,
+    args: &[String],
+    limits: &Limits,
+) -> Result<RunOutput, RunError> {
+    execute(binary, args, limits, true)
+}
+
+fn execute(
+    binary: &str,
+    args: &[String],
+    limits: &Limits,
+    backtrace: bool,
+) -> Result<RunOutput, RunError> {
+    let (mut command, _scratch) = sandbox::command(binary).map_err(RunError::Spawn)?;
+    if backtrace {
+        command.env("RUST_BACKTRACE", "1");
+    }
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    apply_limits(&mut command, limits);
+
+    let start = Instant::now();
+    let mut child = command.spawn().map_err(RunError::Spawn)?;
+
+    let (stdout, stdout_overflow) = capture(
+        child.stdout.take().expect("stdout is piped"),
+        limits.max_output,
+    );
+    let (stderr, stderr_overflow) = capture(
+        child.stderr.take().expect("stderr is piped"),
+        limits.max_output,
+    );
+    let overflowed =
+        || stdout_overflow.load(Ordering::Relaxed) || stderr_overflow.load(Ordering::Relaxed);
+
+    let status = loop {
+        if overflowed() {
+            kill(&mut child);
+            return Err(RunError::OutputLimit(limits.max_output));
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if start.elapsed() >= limits.wall_time => {
+                kill(&mut child);
+                return Err(RunError::Timeout(limits.wall_time));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(1)),
+            Err(e) => {
+                kill(&mut child);
+                return Err(RunError::Spawn(e));
+            }
+        }
+    };
+    let elapsed = start.elapsed();
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    if overflowed() {
+        return Err(RunError::OutputLimit(limits.max_output));
+    }
+    if let Some(secs) = limits.cpu_seconds {
+        if killed_by_cpu_limit(&status) {
+            return Err(RunError::CpuLimit(secs));
+        }
+    }
+
+    Ok(RunOutput {
+        status,
+        stdout,
+        stderr,
+        elapsed,
+    })
+}
+
+/// Applies the kernel-enforced parts of `limits` (CPU, memory) to a command
+/// before it is spawned. Wall-clock and output limits are up to the caller.
+pub fn apply_limits(command: &mut Command, limits: &Limits) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        let cpu = limits.cpu_seconds;
+        let memory = limits.memory_bytes;
+        // SAFETY: only async-signal-safe libc calls run between fork and exec.
+        unsafe {
+            command.pre_exec(move || {
+                if let Some(secs) = cpu {
+                    // Hard limit one second later so SIGXCPU arrives before SIGKILL.
+                    let limit = rlimit(secs, secs + 1);
+                    if libc::setrlimit(libc::RLIMIT_CPU, &limit) != 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+                if let Some(bytes) = memory {
+                    let limit = rlimit(bytes, bytes);
+                    if libc::setrlimit(libc::RLIMIT_AS, &limit) != 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (command, limits);
+    }
+}
+
+#[cfg(unix)]
+fn rlimit(soft: u64, hard: u64) -> libc::rlimit {
+    libc::rlimit {
+        rlim_cur: soft as libc::rlim_t,
+        rlim_max: hard as libc::rlim_t,
+    }
+}
+
+#[cfg(unix)]
+fn killed_by_cpu_limit(status: &ExitStatus) -> bool {
+    use std::os::unix::process::ExitStatusExt;
+    matches!(status.signal(), Some(libc::SIGXCPU))
+}
+
+#[cfg(not(unix))]
+fn killed_by_cpu_limit(_status: &ExitStatus) -> bool {
+    false
+}
+
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Reads a stream on a background thread, keeping at most `limit` bytes.
+/// The flag is raised as soon as the stream produces more than that.
+pub fn capture<R: Read + Send + 'static>(
+    stream: R,
+    limit: usize,
+) -> (thread::JoinHandle<String>, Arc<AtomicBool>) {
+    let overflow = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&overflow);
+    let handle = thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stream.take(limit as u64 + 1).read_to_end(&mut buf);
+        if buf.len() > limit {
+            flag.store(true, Ordering::Relaxed);
+            buf.truncate(limit);
+        }
+        String::from_utf8_lossy(&buf).to_string()
+    });
+    (handle, overflow)
+}
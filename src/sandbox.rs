@@ -0,0 +1,201 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::OnceLock;
+
+/// How player firmware is isolated from the host.
+pub enum Isolation {
+    /// Firmware runs in the current directory with the player's environment.
+    Off,
+    /// Scratch directory, empty environment, private network namespace and a
+    /// read-only bind mount of the workspace.
+    Namespaced { workspace: PathBuf },
+    /// Sandbox requested but the kernel refused namespaces. Firmware still gets
+    /// a scratch directory and an empty environment. Carries the reason.
+    Fallback(String),
+}
+
+impl Isolation {
+    /// Short name for reports: "off", "sealed" or "degraded".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Isolation::Off => "off",
+            Isolation::Namespaced { .. } => "sealed",
+            Isolation::Fallback(_) => "degraded",
+        }
+    }
+}
+
+static ISOLATION: OnceLock<Isolation> = OnceLock::new();
+static SCRATCH_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// Turns on sandboxing for every firmware run. Probes whether unprivileged
+/// namespaces are available and falls back to a weaker mode if not.
+pub fn enable(workspace: &Path) -> &'static Isolation {
+    ISOLATION.get_or_init(|| {
+        let workspace = match workspace.canonicalize() {
+            Ok(path) => path,
+            Err(e) => return Isolation::Fallback(format!("workspace unavailable: {}", e)),
+        };
+        match probe(&workspace) {
+            Ok(()) => Isolation::Namespaced { workspace },
+            Err(e) => Isolation::Fallback(format!(
+                "unprivileged namespaces unavailable ({}). Network and workspace are NOT isolated",
+                e
+            )),
+        }
+    })
+}
+
+pub fn isolation() -> &'static Isolation {
+    ISOLATION.get().unwrap_or(&Isolation::Off)
+}
+
+/// Scratch directory a sandboxed firmware runs in. Removed on drop.
+pub struct Scratch(PathBuf);
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Builds the `Command` used to launch player firmware, applying the active
+/// isolation mode. Keep the returned `Scratch` alive until the process exits.
+pub fn command(binary: &str) -> io::Result<(Command, Option<Scratch>)> {
+    let workspace = match isolation() {
+        Isolation::Off => return Ok((Command::new(binary), None)),
+        Isolation::Namespaced { workspace } => Some(workspace),
+        Isolation::Fallback(_) => None,
+    };
+
+    // The working directory changes, so resolve the binary first.
+    let binary = Path::new(binary).canonicalize()?;
+    let scratch = std::env::temp_dir().join(format!(
+        "rust_survival-{}-{}",
+        std::process::id(),
+        SCRATCH_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(&scratch)?;
+
+    let mut command = Command::new(binary);
+    command.current_dir(&scratch).env_clear();
+    if let Some(workspace) = workspace {
+        namespaces::apply(&mut command, workspace)?;
+    }
+    Ok((command, Some(Scratch(scratch))))
+}
+
+/// Launches `true` inside the full sandbox to see if the kernel allows it.
+fn probe(workspace: &Path) -> io::Result<()> {
+    let mut command = Command::new("true");
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    namespaces::apply(&mut command, workspace)?;
+    let status = command.status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("probe exited with {}", status)))
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod namespaces {
+    use std::ffi::CString;
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::process::CommandExt;
+    use std::path::Path;
+    use std::process::Command;
+    use std::ptr;
+
+    /// Runs the child in fresh user, mount and network namespaces with the
+    /// workspace bind-mounted read-only over itself.
+    pub fn apply(command: &mut Command, workspace: &Path) -> io::Result<()> {
+        // Everything the child needs is prepared here: no allocation after fork.
+        let target = CString::new(workspace.as_os_str().as_bytes())?;
+        let root = CString::new("/")?;
+        let locked = locked_flags(&target)?;
+
+        // SAFETY: only raw syscalls on pre-built C strings run between fork and exec.
+        unsafe {
+            command.pre_exec(move || {
+                let flags = libc::CLONE_NEWUSER | libc::CLONE_NEWNS | libc::CLONE_NEWNET;
+                check(libc::unshare(flags))?;
+                // Keep our mounts from propagating back to the host.
+                check(libc::mount(
+                    ptr::null(),
+                    root.as_ptr(),
+                    ptr::null(),
+                    libc::MS_REC | libc::MS_PRIVATE,
+                    ptr::null(),
+                ))?;
+                check(libc::mount(
+                    target.as_ptr(),
+                    target.as_ptr(),
+                    ptr::null(),
+                    libc::MS_BIND | libc::MS_REC,
+                    ptr::null(),
+                ))?;
+                check(libc::mount(
+                    ptr::null(),
+                    target.as_ptr(),
+                    ptr::null(),
+                    libc::MS_BIND | libc::MS_REMOUNT | libc::MS_RDONLY | locked,
+                    ptr::null(),
+                ))?;
+                Ok(())
+            });
+        }
+        Ok(())
+    }
+
+    /// Mount flags the kernel won't let a user namespace clear on remount.
+    fn locked_flags(path: &CString) -> io::Result<libc::c_ulong> {
+        // SAFETY: `stat` is plain data and fully written by a successful statvfs.
+        let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+        check(unsafe { libc::statvfs(path.as_ptr(), &mut stat) })?;
+
+        let mut flags = 0;
+        for (st, ms) in [
+            (libc::ST_NOSUID, libc::MS_NOSUID),
+            (libc::ST_NODEV, libc::MS_NODEV),
+            (libc::ST_NOEXEC, libc::MS_NOEXEC),
+            (libc::ST_NOATIME, libc::MS_NOATIME),
+            (libc::ST_NODIRATIME, libc::MS_NODIRATIME),
+            (libc::ST_RELATIME, libc::MS_RELATIME),
+        ] {
+            if stat.f_flag & st != 0 {
+                flags |= ms;
+            }
+        }
+        Ok(flags)
+    }
+
+    fn check(ret: libc::c_int) -> io::Result<()> {
+        if ret == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod namespaces {
+    use std::io;
+    use std::path::Path;
+    use std::process::Command;
+
+    pub fn apply(_command: &mut Command, _workspace: &Path) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "namespaces are Linux-only",
+        ))
+    }
+}
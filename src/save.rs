@@ -0,0 +1,108 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Bump when the layout changes in a way older builds can't read.
+pub const SAVE_VERSION: u32 = 1;
+
+/// Campaign progress file, stored next to the `missions/` folder.
+pub const SAVE_FILE: &str = ".rust_survival_save.toml";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveData {
+    pub version: u32,
+    /// Ids of completed missions.
+    #[serde(default)]
+    pub completed: BTreeSet<u32>,
+    /// Id of the mission that was last started.
+    #[serde(default)]
+    pub last_mission: Option<u32>,
+    #[serde(default)]
+    pub records: Vec<MissionRecord>,
+}
+
+/// Best results achieved on a completed mission.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissionRecord {
+    pub id: u32,
+    /// Fastest firmware run, in microseconds.
+    pub best_latency_us: Option<u64>,
+    /// Size of the binary that completed the mission, in bytes.
+    pub binary_size: Option<u64>,
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            version: SAVE_VERSION,
+            completed: BTreeSet::new(),
+            last_mission: None,
+            records: Vec::new(),
+        }
+    }
+}
+
+impl SaveData {
+    /// Reads the save file. A missing file is a fresh campaign.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let save: SaveData = toml::from_str(&raw)
+            .with_context(|| format!("Invalid save file {}", path.display()))?;
+
+        if save.version > SAVE_VERSION {
+            bail!(
+                "Save file {} is version {}, this build only understands up to {}",
+                path.display(),
+                save.version,
+                SAVE_VERSION
+            );
+        }
+        Ok(save)
+    }
+
+    pub fn store(&self, path: &Path) -> Result<()> {
+        let raw = toml::to_string_pretty(self).context("Failed to serialize save data")?;
+        // Write to a sibling file first so a crash mid-write can't corrupt progress.
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, raw).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    pub fn record(&self, id: u32) -> Option<&MissionRecord> {
+        self.records.iter().find(|r| r.id == id)
+    }
+
+    /// Marks `id` as completed and keeps the best latency / binary size seen.
+    pub fn complete(&mut self, id: u32, latency: Option<Duration>, binary_size: Option<u64>) {
+        self.completed.insert(id);
+
+        let latency_us = latency.map(|d| d.as_micros() as u64);
+        match self.records.iter_mut().find(|r| r.id == id) {
+            Some(record) => {
+                record.best_latency_us = min_some(record.best_latency_us, latency_us);
+                record.binary_size = min_some(record.binary_size, binary_size);
+            }
+            None => self.records.push(MissionRecord {
+                id,
+                best_latency_us: latency_us,
+                binary_size,
+            }),
+        }
+    }
+}
+
+fn min_some(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
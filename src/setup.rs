@@ -1,17 +1,97 @@
-use include_dir::{include_dir, Dir};
-use std::path::Path;
+use include_dir::{include_dir, Dir, File};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::fs; // <--- Brought this back
-use anyhow::{Result, Context};
+use std::time::{SystemTime, UNIX_EPOCH};
+use anyhow::{bail, Result, Context};
 
 // Embed the "missions" directory from the project root into the binary at compile time.
 static MISSIONS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/missions");
 
-pub fn initialize_workspace() -> Result<()> {
+/// Where a mission file lives inside `missions/`, e.g. "02_water.rs" or
+/// "03_radio/src/main.rs". Shipped originals and the install record use this key.
+pub fn mission_key(source: &Path) -> String {
+    let relative = source.strip_prefix("missions").unwrap_or(source);
+    relative
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The shipped version of a mission file, by `mission_key`.
+pub fn original_source(key: &str) -> Option<&'static str> {
+    MISSIONS_DIR.get_file(key)?.contents_utf8()
+}
+
+/// Whether the file at `key` was written by `--init`, `--init --upgrade` or
+/// `--reset` of a build that knows about the install record.
+pub fn is_recorded(key: &str) -> bool {
+    Shipped::load().files.contains_key(key)
+}
+
+/// Every shipped mission file, including those of Cargo projects in subdirectories.
+fn shipped_files() -> Vec<&'static File<'static>> {
+    fn walk(dir: &'static Dir<'static>, files: &mut Vec<&'static File<'static>>) {
+        files.extend(dir.files());
+        for sub in dir.dirs() {
+            walk(sub, files);
+        }
+    }
+    let mut files = Vec::new();
+    walk(&MISSIONS_DIR, &mut files);
+    files
+}
+
+/// Fingerprints of the mission files as they were extracted, stored next to
+/// the `missions/` folder. Lets `--upgrade` tell player edits apart from files
+/// that are merely out of date.
+pub const SHIPPED_FILE: &str = ".rust_survival_shipped.toml";
+
+#[derive(Default, Serialize, Deserialize)]
+struct Shipped {
+    /// `mission_key` -> fingerprint of the version we wrote.
+    #[serde(default)]
+    files: BTreeMap<String, String>,
+}
+
+impl Shipped {
+    /// A missing or unreadable record means nothing is known to be pristine.
+    fn load() -> Self {
+        fs::read_to_string(SHIPPED_FILE)
+            .ok()
+            .and_then(|raw| toml::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    fn store(&self) -> Result<()> {
+        let raw = toml::to_string(self).context("Failed to encode install record")?;
+        fs::write(SHIPPED_FILE, raw).with_context(|| format!("Failed to write {}", SHIPPED_FILE))
+    }
+
+    fn record(&mut self, name: &str, contents: &[u8]) {
+        self.files.insert(name.to_string(), fingerprint(contents));
+    }
+}
+
+/// FNV-1a: stable across builds, unlike `std`'s `DefaultHasher`.
+fn fingerprint(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
+
+pub fn initialize_workspace(upgrade: bool) -> Result<()> {
     let target_dir = Path::new("missions");
 
-    if target_dir.exists() {
+    if target_dir.exists() && upgrade {
+        upgrade_workspace(target_dir)?;
+    } else if target_dir.exists() {
         println!("Checked 'missions' folder... exists.");
         println!("Skipping overwrite to protect your progress.");
+        println!("Run with --init --upgrade to add missions shipped with this build.");
     } else {
         println!("Initializing survival workspace...");
         
@@ -21,6 +101,11 @@ pub fn initialize_workspace() -> Result<()> {
         // Extract the embedded directory to the user's disk
         MISSIONS_DIR.extract(target_dir)
             .context("Failed to extract mission files")?;
+        let mut shipped = Shipped::default();
+        for file in shipped_files() {
+            shipped.record(&mission_key(file.path()), file.contents());
+        }
+        shipped.store()?;
             
         println!("✔ Created 'missions/' directory.");
         println!("✔ Extracted mission files.");
@@ -29,3 +114,129 @@ pub fn initialize_workspace() -> Result<()> {
 
     Ok(())
 }
+
+/// Brings an existing workspace up to this build: adds missing files and
+/// updates ones the player never touched. Edited files are reported, not replaced.
+fn upgrade_workspace(target_dir: &Path) -> Result<()> {
+    println!("Upgrading survival workspace...");
+    let mut shipped = Shipped::load();
+    let (mut current, mut edited) = (0, 0);
+
+    for file in shipped_files() {
+        let name = mission_key(file.path());
+        let path = target_dir.join(file.path());
+        let Ok(existing) = fs::read(&path) else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create {}", parent.display()))?;
+            }
+            fs::write(&path, file.contents())
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            shipped.record(&name, file.contents());
+            println!("✔ Added {}", path.display());
+            continue;
+        };
+
+        let recorded = shipped.files.get(&name);
+        if existing == file.contents() {
+            shipped.record(&name, file.contents());
+            current += 1;
+        } else if recorded == Some(&fingerprint(file.contents())) {
+            // Upstream hasn't changed since install; the difference is the player's work.
+            edited += 1;
+        } else if recorded == Some(&fingerprint(&existing)) {
+            fs::write(&path, file.contents())
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            shipped.record(&name, file.contents());
+            println!("✔ Updated {} (you had not edited it)", path.display());
+        } else {
+            let why = if recorded.is_some() {
+                "you edited it"
+            } else {
+                "it may contain your work"
+            };
+            println!(
+                "! Kept {}: {}, but this build ships a newer version.",
+                path.display(),
+                why
+            );
+            // Only top-level sources map straight to a mission name.
+            let top_level = file.path().parent() == Some(Path::new(""));
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("rs") if top_level => println!(
+                    "  Run --reset {} to take it (your version is backed up first).",
+                    file.path().with_extension("").display()
+                ),
+                _ => println!("  Move your copy aside and run --init --upgrade again to take it."),
+            }
+        }
+    }
+
+    shipped.store()?;
+    println!(
+        "  {} file(s) already up to date, {} holding your own edits.",
+        current, edited
+    );
+    Ok(())
+}
+
+/// Restores one mission file to the version shipped with the game. The
+/// current file is first moved to `<name>.<timestamp>.bak` next to it.
+/// Returns the backup path, or `None` if there was no file to back up.
+pub fn reset_mission(source: &Path) -> Result<Option<PathBuf>> {
+    let key = mission_key(source);
+    let Some(original) = MISSIONS_DIR.get_file(&key) else {
+        bail!("No original of '{}' ships with this build.", key);
+    };
+    let name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let backup = if source.exists() {
+        let backup = source.with_file_name(format!("{}.{}.bak", name, timestamp()));
+        fs::rename(source, &backup)
+            .with_context(|| format!("Failed to back up {}", source.display()))?;
+        Some(backup)
+    } else {
+        None
+    };
+
+    fs::write(source, original.contents())
+        .with_context(|| format!("Failed to restore {}", source.display()))?;
+    // The record only helps later upgrades; the restore itself already worked.
+    let mut shipped = Shipped::load();
+    shipped.record(&key, original.contents());
+    let _ = shipped.store();
+    Ok(backup)
+}
+
+/// Current UTC time as `YYYYMMDD-HHMMSS`, for backup file names.
+fn timestamp() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
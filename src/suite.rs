@@ -0,0 +1,171 @@
+use crate::crash;
+use crate::job::Job;
+use crate::runner::{self, Limits, RunError};
+use std::fmt;
+use std::io;
+use std::process::ExitStatus;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// How many randomized cases each mission adds on top of its fixed edge cases.
+pub const RANDOM_CASES: usize = 16;
+
+/// One hidden test: the arguments passed to the firmware and the value the
+/// reference logic produces for them.
+pub struct TestCase {
+    pub args: Vec<String>,
+    pub expected: f64,
+}
+
+impl TestCase {
+    pub fn new<T: ToString>(args: &[T], expected: f64) -> Self {
+        Self {
+            args: args.iter().map(ToString::to_string).collect(),
+            expected,
+        }
+    }
+}
+
+/// A mission's hidden tests for one build: every case must print a number
+/// within `tolerance` of its expected value.
+pub struct TestSuite {
+    pub binary: String,
+    pub cases: Vec<TestCase>,
+    pub tolerance: f64,
+}
+
+/// Everything known about the first case the firmware got wrong, shown as
+/// an expected/actual panel in the LOGS tab.
+pub struct FailureReport {
+    pub args: Vec<String>,
+    pub expected: String,
+    pub stdout: String,
+    pub stderr: String,
+    /// The exit status, or why the watchdog killed the firmware.
+    pub status: Result<ExitStatus, String>,
+    pub elapsed: Duration,
+}
+
+impl FailureReport {
+    /// How the process ended, e.g. "exit code 101", "SIGSEGV (segmentation
+    /// fault)" or the watchdog's reason for killing it.
+    pub fn exit_summary(&self) -> String {
+        match &self.status {
+            Ok(status) => crash::describe_status(status),
+            Err(fault) => fault.clone(),
+        }
+    }
+}
+
+/// Result of running a mission's hidden test suite.
+pub struct SuiteReport {
+    pub passed: usize,
+    pub total: usize,
+    pub first_failure: Option<FailureReport>,
+}
+
+impl SuiteReport {
+    pub fn all_passed(&self) -> bool {
+        self.passed == self.total
+    }
+
+    /// One-line verdict used as the mission status when the suite fails.
+    pub fn verdict(&self) -> String {
+        format!(
+            "LOGIC ERROR: firmware failed {} of {} hidden tests.",
+            self.total - self.passed,
+            self.total
+        )
+    }
+}
+
+impl fmt::Display for SuiteReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "HIDDEN TEST SUITE: {}/{} PASSED",
+            self.passed, self.total
+        )
+    }
+}
+
+/// A hidden test suite running on a worker thread. With up to 500ms per
+/// case, running it on the UI thread would freeze the game.
+pub type SuiteJob = Job<Result<SuiteReport, RunError>>;
+
+pub fn start(suite: TestSuite) -> SuiteJob {
+    Job::spawn(
+        move |cancel| run(&suite, cancel),
+        || Err(RunError::Spawn(io::Error::other("test worker thread died"))),
+    )
+}
+
+/// Keeps the hidden test results in `report`. A firmware that could not be
+/// started or failed any case fails the mission.
+pub fn record(
+    report: &mut Option<SuiteReport>,
+    tests: Result<SuiteReport, RunError>,
+) -> Result<(), String> {
+    *report = None;
+    let tests = tests.map_err(|e| e.to_string())?;
+    let result = if tests.all_passed() {
+        Ok(())
+    } else {
+        Err(tests.verdict())
+    };
+    *report = Some(tests);
+    result
+}
+
+/// Runs every case against the suite's binary. Output is parsed as a number and must be
+/// within `tolerance` of the expected value. A case the watchdog has to kill
+/// counts as failed; only a binary that cannot be started at all aborts the run.
+/// Setting `cancel` stops before the next case.
+pub fn run(suite: &TestSuite, cancel: &AtomicBool) -> Result<SuiteReport, RunError> {
+    let mut report = SuiteReport {
+        passed: 0,
+        total: suite.cases.len(),
+        first_failure: None,
+    };
+
+    for case in &suite.cases {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let started = Instant::now();
+        let out = match runner::run(&suite.binary, &case.args, &Limits::default()) {
+            Ok(out) => out,
+            Err(e @ RunError::Spawn(_)) => return Err(e),
+            Err(fault) => {
+                report.first_failure.get_or_insert_with(|| FailureReport {
+                    args: case.args.clone(),
+                    expected: format!("{:.2}", case.expected),
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    status: Err(fault.to_string()),
+                    elapsed: started.elapsed(),
+                });
+                continue;
+            }
+        };
+        let actual = out.stdout.trim();
+        let passed = out.status.success()
+            && actual
+                .parse::<f64>()
+                .is_ok_and(|value| (value - case.expected).abs() <= suite.tolerance);
+
+        if passed {
+            report.passed += 1;
+        } else if report.first_failure.is_none() {
+            report.first_failure = Some(FailureReport {
+                args: case.args.clone(),
+                expected: format!("{:.2}", case.expected),
+                stdout: out.stdout,
+                stderr: out.stderr,
+                status: Ok(out.status),
+                elapsed: out.elapsed,
+            });
+        }
+    }
+    Ok(report)
+}
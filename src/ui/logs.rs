@@ -0,0 +1,234 @@
+use crate::app::App;
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::suite::FailureReport;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, Wrap},
+    Frame,
+};
+
+/// The LOGS tab contents, flattened to lines.
+pub struct LogLayout {
+    pub lines: Vec<Line<'static>>,
+    /// Line index where each compiler diagnostic starts, for jumping between them.
+    pub diagnostic_offsets: Vec<usize>,
+}
+
+/// Builds the LOGS tab: the status message followed by any compiler diagnostics.
+pub fn log_layout(app: &App) -> LogLayout {
+    let text_style = Style::default().fg(Color::Yellow);
+    let mut lines: Vec<Line<'static>> = app
+        .get_log_content()
+        .lines()
+        .map(|l| Line::styled(l.to_string(), text_style))
+        .collect();
+
+    let mut diagnostic_offsets = Vec::new();
+    for (i, diagnostic) in app.active_mission.diagnostics.iter().enumerate() {
+        lines.push(Line::from(""));
+        diagnostic_offsets.push(lines.len());
+        push_diagnostic(&mut lines, diagnostic, i == app.diagnostic_index);
+    }
+
+    LogLayout {
+        lines,
+        diagnostic_offsets,
+    }
+}
+
+pub fn render_logs(f: &mut Frame, app: &App, area: Rect) {
+    let failure = app
+        .state
+        .test_report()
+        .and_then(|report| report.first_failure.as_ref());
+    let area = match failure {
+        Some(failure) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(FAILURE_PANEL_HEIGHT), Constraint::Min(0)])
+                .split(area);
+            render_failure(f, failure, chunks[0]);
+            chunks[1]
+        }
+        None => area,
+    };
+
+    let layout = log_layout(app);
+    let mut paragraph = Paragraph::new(layout.lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" TERMINAL OUTPUT "),
+        )
+        .scroll((app.vertical_scroll, 0));
+    // Diagnostics are laid out line by line (carets must stay under their column),
+    // so only raw text output gets wrapped.
+    if layout.diagnostic_offsets.is_empty() {
+        paragraph = paragraph.wrap(Wrap { trim: true });
+    }
+    f.render_widget(paragraph, area);
+    f.render_stateful_widget(
+        Scrollbar::default().orientation(ScrollbarOrientation::VerticalRight),
+        area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut app.scroll_state.clone(),
+    );
+}
+
+const FAILURE_PANEL_HEIGHT: u16 = 11;
+
+/// Expected vs. actual output for the first failing hidden test, side by side.
+fn render_failure(f: &mut Frame, failure: &FailureReport, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" LOGIC FAILURE ")
+        .border_style(Style::default().fg(Color::Red));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
+    let label = Style::default().fg(Color::DarkGray);
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(" INPUT: ", label),
+            Span::raw(failure.args.join(" ")),
+            Span::styled("   EXIT: ", label),
+            Span::raw(failure.exit_summary()),
+            Span::styled("   TIME: ", label),
+            Span::raw(format!("{:.2?}", failure.elapsed)),
+        ])),
+        rows[0],
+    );
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+    f.render_widget(
+        Paragraph::new(Line::styled(
+            failure.expected.clone(),
+            Style::default().fg(Color::Green),
+        ))
+        .block(Block::default().borders(Borders::ALL).title(" EXPECTED ")),
+        columns[0],
+    );
+
+    let stdout = failure.stdout.trim_end();
+    let mut actual = vec![if stdout.is_empty() {
+        Line::styled("(no output)", label)
+    } else {
+        Line::styled(stdout.to_string(), Style::default().fg(Color::Red))
+    }];
+    let stderr = failure.stderr.trim_end();
+    if !stderr.is_empty() {
+        actual.push(Line::styled("stderr:", label));
+        actual.extend(
+            stderr
+                .lines()
+                .map(|l| Line::styled(l.to_string(), Style::default().fg(Color::Yellow))),
+        );
+    }
+    f.render_widget(
+        Paragraph::new(actual)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::ALL).title(" ACTUAL ")),
+        columns[1],
+    );
+}
+
+fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Error => Color::Red,
+        Severity::Warning => Color::Yellow,
+        Severity::Note => Color::Cyan,
+        Severity::Help => Color::Green,
+    }
+}
+
+fn push_diagnostic(lines: &mut Vec<Line<'static>>, diagnostic: &Diagnostic, selected: bool) {
+    let color = severity_color(diagnostic.severity);
+    let gutter = Style::default()
+        .fg(Color::Blue)
+        .add_modifier(Modifier::BOLD);
+
+    let heading = match &diagnostic.code {
+        Some(code) => format!("{}[{}]", diagnostic.severity.label(), code),
+        None => diagnostic.severity.label().to_string(),
+    };
+    let mut heading_style = Style::default().fg(color).add_modifier(Modifier::BOLD);
+    if selected {
+        heading_style = heading_style.add_modifier(Modifier::REVERSED);
+    }
+    lines.push(Line::from(vec![
+        Span::styled(if selected { "▶ " } else { "  " }, heading_style),
+        Span::styled(heading, heading_style),
+        Span::styled(
+            format!(": {}", diagnostic.message),
+            Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ]));
+
+    for span in &diagnostic.spans {
+        lines.push(Line::from(vec![
+            Span::styled("   --> ", gutter),
+            Span::raw(format!("{}:{}:{}", span.file, span.line, span.column)),
+        ]));
+        let Some(source) = &span.source_line else {
+            continue;
+        };
+
+        let (mark, mark_color) = if span.is_primary {
+            ("^", color)
+        } else {
+            ("-", Color::Blue)
+        };
+        let width = span.column_end.saturating_sub(span.column).max(1);
+        let underline = format!(
+            "{}{} {}",
+            " ".repeat(span.column.saturating_sub(1)),
+            mark.repeat(width),
+            span.label.clone().unwrap_or_default()
+        );
+
+        lines.push(Line::styled("      |", gutter));
+        lines.push(Line::from(vec![
+            Span::styled(format!(" {:>4} | ", span.line), gutter),
+            Span::raw(source.replace('\t', " ")),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("      | ", gutter),
+            Span::styled(
+                underline,
+                Style::default().fg(mark_color).add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+
+    for child in &diagnostic.children {
+        lines.push(Line::from(vec![
+            Span::styled("      = ", gutter),
+            Span::styled(
+                format!("{}: ", child.severity.label()),
+                Style::default()
+                    .fg(severity_color(child.severity))
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(child.message.clone()),
+        ]));
+        if let Some(replacement) = &child.replacement {
+            lines.push(Line::from(vec![
+                Span::styled("        suggestion: ", Style::default().fg(Color::Green)),
+                Span::raw(format!("`{}`", replacement)),
+            ]));
+        }
+    }
+}
@@ -1,11 +1,15 @@
-use crate::app::{App, MenuItem};
+use crate::app::App;
+use crate::gameplay::MissionStatus;
+use crate::rules;
+use crate::sandbox::{self, Isolation};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph},
+    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph, Wrap},
     Frame,
 };
+use std::time::Duration;
 
 const RUST_ORANGE: Color = Color::Rgb(183, 65, 14);
 
@@ -21,7 +25,7 @@ pub fn render_main_menu(f: &mut Frame, app: &App) {
         .split(f.area());
 
     // Logo
-    let logo_text = vec![
+    let logo_text = [
     "██████╗ ██╗   ██╗███████╗████████╗     ███████╗██╗   ██╗██████╗ ██╗   ██╗██╗██╗   ██╗ █████╗ ██╗     ", 
     "██╔══██╗██║   ██║██╔════╝╚══██╔══╝     ██╔════╝██║   ██║██╔══██╗██║   ██║██║██║   ██║██╔══██╗██║     ",
     "██████╔╝██║   ██║███████╗   ██║        ███████╗██║   ██║██████╔╝██║   ██║██║██║   ██║███████║██║     ",
@@ -51,7 +55,7 @@ pub fn render_main_menu(f: &mut Frame, app: &App) {
         .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
         .split(chunks[1]);
 
-    let menu_list = MenuItem::all();
+    let menu_list = app.menu_items();
     let items: Vec<ListItem> = menu_list
         .iter()
         .enumerate()
@@ -100,14 +104,60 @@ pub fn render_main_menu(f: &mut Frame, app: &App) {
             Span::raw("NETWORK: "),
             Span::styled("OFFLINE", Style::default().fg(Color::Red)),
         ]),
+        match sandbox::isolation() {
+            Isolation::Off => Line::from(vec![
+                Span::raw("SANDBOX: "),
+                Span::styled("DISABLED", Style::default().fg(Color::DarkGray)),
+            ]),
+            Isolation::Namespaced { .. } => Line::from(vec![
+                Span::raw("SANDBOX: "),
+                Span::styled("SEALED", Style::default().fg(Color::Green)),
+            ]),
+            Isolation::Fallback(reason) => Line::from(vec![
+                Span::raw("SANDBOX: "),
+                Span::styled(
+                    format!("DEGRADED - {}", reason),
+                    Style::default().fg(Color::Yellow),
+                ),
+            ]),
+        },
+        if rules::hardcore() {
+            Line::from(vec![
+                Span::raw("HARDCORE: "),
+                Span::styled("ON - BANNED CODE IS FATAL", Style::default().fg(Color::Red)),
+            ])
+        } else {
+            Line::from(vec![
+                Span::raw("HARDCORE: "),
+                Span::styled("OFF", Style::default().fg(Color::DarkGray)),
+            ])
+        },
+        match &app.save_error {
+            None => Line::from(vec![
+                Span::raw("STORAGE: "),
+                Span::styled(
+                    format!("{} MISSION(S) ARCHIVED", app.save.completed.len()),
+                    Style::default().fg(Color::Green),
+                ),
+            ]),
+            Some(err) => Line::from(vec![
+                Span::raw("STORAGE: "),
+                Span::styled(
+                    format!("WRITE FAILURE ({})", err),
+                    Style::default().fg(Color::Red),
+                ),
+            ]),
+        },
     ];
     f.render_widget(
-        Paragraph::new(diag_text).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded)
-                .title(" STATUS "),
-        ),
+        Paragraph::new(diag_text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .title(" STATUS "),
+            )
+            .wrap(Wrap { trim: true }),
         menu_chunks[1],
     );
 
@@ -126,33 +176,80 @@ pub fn render_level_selection(f: &mut Frame, app: &App) {
         .margin(2)
         .constraints([Constraint::Min(0)])
         .split(f.area())[0];
-    let missions = App::get_mission_list();
-
-    let items: Vec<ListItem> = missions
+    let items: Vec<ListItem> = app
+        .missions
         .iter()
         .enumerate()
-        .map(|(i, (id, title, desc))| {
+        .map(|(i, mission)| {
             let is_selected = i == app.mission_selection_index;
-            let header_style = if is_selected {
-                Style::default()
+            let status = app.mission_status(mission);
+            let is_locked = status == MissionStatus::Locked;
+            let header_style = match (is_selected, is_locked) {
+                (true, false) => Style::default()
                     .fg(RUST_ORANGE)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+                (true, true) => Style::default()
+                    .fg(Color::Gray)
+                    .add_modifier(Modifier::BOLD),
+                (false, true) => Style::default().fg(Color::DarkGray),
+                (false, false) => Style::default().fg(Color::White),
             };
             let prefix = if is_selected { " [X] " } else { " [ ] " };
+            let (badge, badge_style) = match status {
+                MissionStatus::Locked => ("  [LOCKED]", Style::default().fg(Color::DarkGray)),
+                MissionStatus::Success => ("  [COMPLETE]", Style::default().fg(Color::Green)),
+                _ => ("", Style::default()),
+            };
 
-            ListItem::new(vec![
+            let mut lines = vec![
                 Line::from(vec![
                     Span::styled(prefix, header_style),
-                    Span::styled(format!("MISSION {:02}: {}", id, title), header_style),
+                    Span::styled(
+                        format!("MISSION {:02}: {}", mission.id, mission.title),
+                        header_style,
+                    ),
+                    Span::styled(
+                        format!("  {}", difficulty_pips(mission.difficulty)),
+                        Style::default().fg(if is_locked {
+                            Color::DarkGray
+                        } else {
+                            RUST_ORANGE
+                        }),
+                    ),
+                    Span::styled(badge, badge_style),
                 ]),
                 Line::from(Span::styled(
-                    format!("      {}", desc),
+                    format!("      {}", mission.description),
                     Style::default().fg(Color::DarkGray),
                 )),
-                Line::from(""),
-            ])
+                Line::from(Span::styled(
+                    format!("      CONCEPTS: {}", mission.concepts.join(", ")),
+                    Style::default().fg(Color::DarkGray),
+                )),
+            ];
+            if let Some(record) = app.save.record(mission.id) {
+                let latency = match record.best_latency_us {
+                    Some(us) => format!("{:.2?}", Duration::from_micros(us)),
+                    None => "--".to_string(),
+                };
+                let size = match record.binary_size {
+                    Some(bytes) => format!("{} B", bytes),
+                    None => "--".to_string(),
+                };
+                lines.push(Line::from(Span::styled(
+                    format!("      RECORD: LATENCY {} | BIN {}", latency, size),
+                    Style::default().fg(Color::Green),
+                )));
+            }
+            if let Some(reason) = app.lock_reason(mission) {
+                lines.push(Line::from(Span::styled(
+                    format!("      {}", reason),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::DIM),
+                )));
+            }
+            lines.push(Line::from(""));
+
+            ListItem::new(lines)
         })
         .collect();
 
@@ -166,3 +263,8 @@ pub fn render_level_selection(f: &mut Frame, app: &App) {
         area,
     );
 }
+
+fn difficulty_pips(difficulty: u8) -> String {
+    let filled = difficulty.min(5) as usize;
+    format!("{}{}", "▮".repeat(filled), "▯".repeat(5 - filled))
+}
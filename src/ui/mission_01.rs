@@ -176,17 +176,35 @@ fn render_sidebar(f: &mut Frame, state: &Mission01State, area: Rect) {
         Some(d) => format!("{:.2?}", d),
         None => "--".to_string(),
     };
+    let benchmark_title = match &state.firmware {
+        Some(fw) if fw.restarts > 0 => format!(" BENCHMARK [LIVE LINK, {} RST] ", fw.restarts),
+        Some(_) => " BENCHMARK [LIVE LINK] ".to_string(),
+        None => " BENCHMARK ".to_string(),
+    };
     f.render_widget(
-        Paragraph::new(format!(" LATENCY: {}", runtime_str))
-            .block(Block::default().borders(Borders::ALL).title(" BENCHMARK ")),
+        Paragraph::new(format!(" LATENCY: {}", runtime_str)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(benchmark_title),
+        ),
         chunks[2],
     );
 
-    f.render_widget(
-        Paragraph::new(format!("> {}", state.gps_output))
+    let stdout = match &state.panic {
+        Some(panic) => Paragraph::new(format!("> {}", panic))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" FIRMWARE PANIC ")
+                    .border_style(Style::default().fg(Color::Red)),
+            )
+            .style(Style::default().fg(Color::Red)),
+        None => Paragraph::new(format!("> {}", state.gps_output))
             .block(Block::default().borders(Borders::ALL).title(" STDOUT "))
-            .wrap(ratatui::widgets::Wrap { trim: true })
             .style(Style::default().fg(Color::DarkGray)),
+    };
+    f.render_widget(
+        stdout.wrap(ratatui::widgets::Wrap { trim: true }),
         chunks[3],
     );
 }
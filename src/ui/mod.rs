@@ -1,7 +1,10 @@
+pub mod logs;
 pub mod menu;
 pub mod mission_01;
 pub mod mission_02;
 pub mod shared;
+pub mod source;
+pub mod terminal;
 
 use crate::app::{App, CurrentScreen};
 use ratatui::Frame;
@@ -1,16 +1,15 @@
 use crate::app::App;
-use crate::gameplay::GameState;
-use crate::ui::mission_01;
-use crate::ui::mission_02;
+use crate::ui::{logs, source, terminal};
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Margin},
+    layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, Tabs, Wrap},
+    widgets::{Block, Borders, Paragraph, Tabs},
     Frame,
 };
 
 const RUST_ORANGE: Color = Color::Rgb(183, 65, 14);
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
 
 pub fn render_gameplay_shell(f: &mut Frame, app: &App) {
     let chunks = Layout::default()
@@ -27,11 +26,42 @@ pub fn render_gameplay_shell(f: &mut Frame, app: &App) {
         Some(bytes) => format!(" [BIN: {} B]", bytes),
         None => "".to_string(),
     };
-    let tabs = Tabs::new(vec![" [1] MISSION ", " [2] LOGS "])
-        .block(Block::default().borders(Borders::ALL).title(format!(
-            " MISSION: {}{} ",
-            app.active_mission.title, size_info
-        )))
+    let running = match (&app.compile_job, &app.verify_job) {
+        (Some(job), _) => Some(("COMPILING", job.elapsed())),
+        (None, Some(job)) => Some(("VERIFYING", job.elapsed())),
+        (None, None) => None,
+    };
+    let compile_info = match running {
+        Some((stage, elapsed)) => {
+            let frame = (elapsed.as_millis() / 80) as usize % SPINNER.len();
+            format!(
+                " {} {}... {:.1}s ",
+                SPINNER[frame],
+                stage,
+                elapsed.as_secs_f32()
+            )
+        }
+        None => "".to_string(),
+    };
+    let tabs = Tabs::new(vec![" [1] MISSION ", " [2] LOGS ", " [3] SOURCE ", " [4] TERMINAL "])
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(
+                    " MISSION {:02}: {}{} ",
+                    app.active_mission.id, app.active_mission.title, size_info
+                ))
+                .title(
+                    Line::from(Span::styled(
+                        compile_info,
+                        Style::default()
+                            .fg(Color::Black)
+                            .bg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    ))
+                    .right_aligned(),
+                ),
+        )
         .select(app.current_tab)
         .highlight_style(
             Style::default()
@@ -40,14 +70,12 @@ pub fn render_gameplay_shell(f: &mut Frame, app: &App) {
         );
     f.render_widget(tabs, chunks[0]);
 
-    // 2. Main Content (Mission or Logs)
+    // 2. Main Content (Mission, Logs, Source or Terminal)
     match app.current_tab {
-        0 => match &app.state {
-            GameState::Mission01(s) => mission_01::render(f, s, chunks[1]),
-            GameState::Mission02(s) => mission_02::render(f, s, chunks[1]),
-            _ => {}
-        },
-        1 => render_logs(f, app, chunks[1]),
+        0 => app.state.render(f, chunks[1]),
+        1 => logs::render_logs(f, app, chunks[1]),
+        2 => source::render(f, app, chunks[1]),
+        3 => terminal::render(f, app, chunks[1]),
         _ => {}
     }
 
@@ -61,40 +89,56 @@ pub fn render_gameplay_shell(f: &mut Frame, app: &App) {
     );
 }
 
-fn render_logs(f: &mut Frame, app: &App, area: ratatui::layout::Rect) {
-    let content = app.get_log_content();
-    f.render_widget(
-        Paragraph::new(content)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(" TERMINAL OUTPUT "),
-            )
-            .style(Style::default().fg(Color::Yellow))
-            .wrap(Wrap { trim: true })
-            .scroll((app.vertical_scroll, 0)),
-        area,
-    );
-    f.render_stateful_widget(
-        Scrollbar::default().orientation(ScrollbarOrientation::VerticalRight),
-        area.inner(Margin {
-            vertical: 1,
-            horizontal: 0,
-        }),
-        &mut app.scroll_state.clone(),
-    );
-}
-
-fn get_footer_status(app: &App) -> (&'static str, Style) {
-    let is_finished = match &app.state {
-        GameState::Mission01(s) => s.is_finished,
-        GameState::Mission02(s) => s.is_finished,
-        _ => false,
+fn get_footer_status(app: &App) -> (String, Style) {
+    let auto = if app.auto_compile {
+        "[A] AUTO-COMPILE: ON "
+    } else {
+        "[A] Auto-Compile: Off "
     };
-
-    if is_finished {
+    if app.terminal_focused {
+        (
+            " TERMINAL FOCUSED - keys go to the shell | [F2] Return to Game ".to_string(),
+            Style::default()
+                .bg(RUST_ORANGE)
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else if app.reset_pending {
+        (
+            " RESTORE ORIGINAL SOURCE? Your file is backed up first. | [Y] Confirm | Any other key cancels "
+                .to_string(),
+            Style::default()
+                .bg(Color::Red)
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else if let Some(notice) = &app.notice {
+        (
+            notice.clone(),
+            Style::default()
+                .bg(Color::Green)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else if app.compile_job.is_some() {
+        (
+            " COMPILING... | [ESC] Cancel ".to_string(),
+            Style::default()
+                .bg(Color::Yellow)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else if app.verify_job.is_some() {
         (
-            " MISSION COMPLETE. PRESS [ENTER] TO CONTINUE. ",
+            " RUNNING HIDDEN TESTS... | [ESC] Cancel ".to_string(),
+            Style::default()
+                .bg(Color::Yellow)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else if app.state.is_finished() {
+        (
+            " MISSION COMPLETE. PRESS [ENTER] TO CONTINUE. ".to_string(),
             Style::default()
                 .bg(Color::Green)
                 .fg(Color::Black)
@@ -102,12 +146,39 @@ fn get_footer_status(app: &App) -> (&'static str, Style) {
         )
     } else if app.current_tab == 0 {
         (
-            " [Arrows] Move | [C] Compile Code | [TAB] View Logs ",
+            format!("{}| {}", app.state.footer_hints(), auto),
+            Style::default().bg(Color::DarkGray).fg(Color::White),
+        )
+    } else if app.current_tab == 2 {
+        (
+            format!(
+                " [Up/Down] Scroll | [PgUp/PgDn] Page | [C] Compile | [E] Edit | [R] Restore Original | {}",
+                auto
+            ),
+            Style::default().bg(Color::DarkGray).fg(Color::White),
+        )
+    } else if app.current_tab == 3 {
+        (
+            format!(
+                " [F2] Focus Terminal | [TAB] Switch Tab | [C] Compile | {}",
+                auto
+            ),
+            Style::default().bg(Color::DarkGray).fg(Color::White),
+        )
+    } else if !app.active_mission.diagnostics.is_empty() {
+        (
+            format!(
+                " [Up/Down] Select Diagnostic | [PgUp/PgDn] Scroll | [C] Re-Compile | [E] Edit | {}",
+                auto
+            ),
             Style::default().bg(Color::DarkGray).fg(Color::White),
         )
     } else {
         (
-            " [Up/Down] Scroll Logs | [C] Re-Compile ",
+            format!(
+                " [Up/Down] Scroll Logs | [C] Re-Compile | [E] Edit | {}",
+                auto
+            ),
             Style::default().bg(Color::DarkGray).fg(Color::White),
         )
     }
@@ -0,0 +1,360 @@
+use crate::app::App;
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::harness;
+use crate::rules::Violation;
+use ratatui::{
+    layout::{Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
+    Frame,
+};
+use std::collections::HashMap;
+use std::path::Path;
+
+const RUST_ORANGE: Color = Color::Rgb(183, 65, 14);
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while",
+];
+
+const PRIMITIVES: &[&str] = &[
+    "bool", "char", "str", "f32", "f64", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16",
+    "u32", "u64", "u128", "usize",
+];
+
+/// The SOURCE tab: the active mission file with line numbers, highlighting
+/// and a gutter marking the last compile's errors and warnings.
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let path = &app.active_mission.path;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" SOURCE: {} ", path.display()))
+        .title(
+            Line::from(vec![
+                Span::styled("▌", Style::default().fg(Color::DarkGray)),
+                Span::raw(" harness  "),
+                Span::styled("●", Style::default().fg(Color::Red)),
+                Span::raw(" error  "),
+                Span::styled("●", Style::default().fg(Color::Yellow)),
+                Span::raw(" warning  "),
+                Span::styled("!", Style::default().fg(Color::Magenta)),
+                Span::raw(" banned "),
+            ])
+            .right_aligned(),
+        );
+
+    let Some(source) = &app.source else {
+        f.render_widget(
+            Paragraph::new(format!(
+                "Could not read {}.\n\nRun --init to restore missing mission files.",
+                path.display()
+            ))
+            .block(block)
+            .style(Style::default().fg(Color::Red)),
+            area,
+        );
+        return;
+    };
+
+    let mission = &app.active_mission;
+    let lines = source_lines(source, path, &mission.diagnostics, &mission.violations);
+    let line_count = lines.len();
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .scroll((app.source_scroll, 0)),
+        area,
+    );
+    f.render_stateful_widget(
+        Scrollbar::default().orientation(ScrollbarOrientation::VerticalRight),
+        area.inner(Margin {
+            vertical: 1,
+            horizontal: 0,
+        }),
+        &mut ScrollbarState::new(line_count).position(app.source_scroll as usize),
+    );
+}
+
+/// Builds one display line per source line: gutter marker, line number,
+/// highlighted code and, on flagged lines, the diagnostic message. Banned
+/// constructs are only marked where the compiler has nothing to say.
+fn source_lines(
+    source: &str,
+    path: &Path,
+    diagnostics: &[Diagnostic],
+    violations: &[Violation],
+) -> Vec<Line<'static>> {
+    let markers = line_markers(path, diagnostics);
+    let protected = harness::protected_ranges(source);
+    let width = source.lines().count().max(1).to_string().len();
+    let mut comment_depth = 0;
+
+    source
+        .lines()
+        .enumerate()
+        .map(|(i, text)| {
+            let in_harness = protected.iter().any(|range| range.contains(&i));
+            let marker = markers.get(&(i + 1));
+            let banned = violations
+                .iter()
+                .find(|v| v.line == i + 1 && v.file == path);
+
+            let mut spans = vec![match marker {
+                Some((Severity::Error, _)) => Span::styled("●", Style::default().fg(Color::Red)),
+                Some(_) => Span::styled("●", Style::default().fg(Color::Yellow)),
+                None if banned.is_some() => Span::styled("!", Style::default().fg(Color::Magenta)),
+                None => Span::raw(" "),
+            }];
+            spans.push(Span::styled(
+                format!("{:>width$} ", i + 1, width = width),
+                Style::default().fg(Color::DarkGray),
+            ));
+            spans.push(if in_harness {
+                Span::styled("▌", Style::default().fg(Color::DarkGray))
+            } else {
+                Span::raw(" ")
+            });
+
+            let code = highlight(&text.replace('\t', "    "), &mut comment_depth);
+            if in_harness {
+                // Harness code is reference material: keep it readable, but muted.
+                spans.extend(code.into_iter().map(|span| {
+                    let style = span.style.add_modifier(Modifier::DIM);
+                    span.style(style)
+                }));
+            } else {
+                spans.extend(code);
+            }
+
+            if let Some((severity, message)) = marker {
+                let color = match severity {
+                    Severity::Error => Color::Red,
+                    _ => Color::Yellow,
+                };
+                spans.push(Span::styled(
+                    format!("  ◀ {}", message),
+                    Style::default().fg(color).add_modifier(Modifier::ITALIC),
+                ));
+            } else if let Some(violation) = banned {
+                spans.push(Span::styled(
+                    format!("  ◀ `{}` is banned in this mission", violation.rule.label()),
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::ITALIC),
+                ));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Most severe diagnostic per 1-based line of the mission file. Spans in other
+/// files (e.g. the standard library) are ignored.
+fn line_markers<'a>(
+    path: &Path,
+    diagnostics: &'a [Diagnostic],
+) -> HashMap<usize, (Severity, &'a str)> {
+    let mut markers: HashMap<usize, (Severity, &str)> = HashMap::new();
+    for diagnostic in diagnostics {
+        if !matches!(diagnostic.severity, Severity::Error | Severity::Warning) {
+            continue;
+        }
+        let Some(span) = diagnostic.primary_span_in(path) else {
+            continue;
+        };
+        let entry = markers
+            .entry(span.line)
+            .or_insert((diagnostic.severity, &diagnostic.message));
+        if entry.0 != Severity::Error && diagnostic.is_error() {
+            *entry = (diagnostic.severity, &diagnostic.message);
+        }
+    }
+    markers
+}
+
+/// Splits one line of Rust into styled tokens. `comment_depth` carries open
+/// (possibly nested) block comments over to the next line.
+fn highlight(line: &str, comment_depth: &mut usize) -> Vec<Span<'static>> {
+    let comment = Style::default()
+        .fg(Color::DarkGray)
+        .add_modifier(Modifier::ITALIC);
+    let chars: Vec<char> = line.chars().collect();
+    let text = |range: std::ops::Range<usize>| chars[range].iter().collect::<String>();
+    let mut spans = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let start = i;
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if *comment_depth > 0 {
+            continue_comment(&chars, &mut i, comment_depth);
+            spans.push(Span::styled(text(start..i), comment));
+            continue;
+        }
+
+        let style = if c == '/' && next == Some('/') {
+            i = chars.len();
+            comment
+        } else if c == '/' && next == Some('*') {
+            *comment_depth = 1;
+            i += 2;
+            continue_comment(&chars, &mut i, comment_depth);
+            comment
+        } else if c == '"' || (c == 'b' && next == Some('"')) {
+            i += if c == 'b' { 2 } else { 1 };
+            skip_string(&chars, &mut i);
+            Style::default().fg(Color::Green)
+        } else if c == 'r' && matches!(next, Some('"') | Some('#')) && is_raw_string(&chars, i) {
+            skip_raw_string(&chars, &mut i);
+            Style::default().fg(Color::Green)
+        } else if c == '\'' {
+            if let Some(end) = char_literal_end(&chars, i) {
+                i = end;
+                Style::default().fg(Color::Green)
+            } else {
+                // Lifetime or label: 'a, 'static.
+                i += 1;
+                while i < chars.len() && is_ident(chars[i]) {
+                    i += 1;
+                }
+                Style::default().fg(Color::LightMagenta)
+            }
+        } else if c.is_ascii_digit() {
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric()
+                    || chars[i] == '_'
+                    || (chars[i] == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit)))
+            {
+                i += 1;
+            }
+            Style::default().fg(Color::Cyan)
+        } else if is_ident(c) {
+            while i < chars.len() && is_ident(chars[i]) {
+                i += 1;
+            }
+            let word = text(start..i);
+            if chars.get(i) == Some(&'!') && !KEYWORDS.contains(&word.as_str()) {
+                i += 1;
+                Style::default().fg(Color::LightBlue)
+            } else if KEYWORDS.contains(&word.as_str()) {
+                Style::default()
+                    .fg(RUST_ORANGE)
+                    .add_modifier(Modifier::BOLD)
+            } else if PRIMITIVES.contains(&word.as_str())
+                || word.starts_with(|c: char| c.is_ascii_uppercase())
+            {
+                Style::default().fg(Color::Yellow)
+            } else if chars.get(i) == Some(&'(') {
+                Style::default().fg(Color::LightCyan)
+            } else {
+                Style::default().fg(Color::White)
+            }
+        } else {
+            i += 1;
+            while i < chars.len() && !starts_token(&chars, i) {
+                i += 1;
+            }
+            Style::default().fg(Color::Gray)
+        };
+        spans.push(Span::styled(text(start..i), style));
+    }
+    spans
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether a new token (anything but plain punctuation/whitespace) begins at `i`.
+fn starts_token(chars: &[char], i: usize) -> bool {
+    let c = chars[i];
+    is_ident(c)
+        || c == '"'
+        || c == '\''
+        || (c == '/' && matches!(chars.get(i + 1), Some('/' | '*')))
+}
+
+/// Advances past the end of a block comment, tracking nesting.
+fn continue_comment(chars: &[char], i: &mut usize, depth: &mut usize) {
+    while *i < chars.len() && *depth > 0 {
+        if chars[*i] == '*' && chars.get(*i + 1) == Some(&'/') {
+            *depth -= 1;
+            *i += 2;
+        } else if chars[*i] == '/' && chars.get(*i + 1) == Some(&'*') {
+            *depth += 1;
+            *i += 2;
+        } else {
+            *i += 1;
+        }
+    }
+}
+
+/// Advances past the closing quote of a string whose opening quote is already
+/// consumed. Strings spanning several lines are only highlighted on the first.
+fn skip_string(chars: &[char], i: &mut usize) {
+    while *i < chars.len() {
+        match chars[*i] {
+            '\\' => *i += 2,
+            '"' => {
+                *i += 1;
+                return;
+            }
+            _ => *i += 1,
+        }
+    }
+    *i = (*i).min(chars.len());
+}
+
+fn is_raw_string(chars: &[char], start: usize) -> bool {
+    // Not part of a longer identifier such as `for`.
+    if start > 0 && is_ident(chars[start - 1]) {
+        return false;
+    }
+    let mut i = start + 1;
+    while chars.get(i) == Some(&'#') {
+        i += 1;
+    }
+    chars.get(i) == Some(&'"')
+}
+
+fn skip_raw_string(chars: &[char], i: &mut usize) {
+    *i += 1;
+    let mut hashes = 0;
+    while chars.get(*i) == Some(&'#') {
+        hashes += 1;
+        *i += 1;
+    }
+    *i += 1;
+    while *i < chars.len() {
+        if chars[*i] == '"'
+            && chars[*i + 1..]
+                .iter()
+                .take(hashes)
+                .filter(|&&c| c == '#')
+                .count()
+                == hashes
+        {
+            *i += 1 + hashes;
+            return;
+        }
+        *i += 1;
+    }
+}
+
+/// End index of a char literal (`'a'`, `'\n'`, `'\u{1F600}'`) starting at `i`,
+/// or `None` if the quote opens a lifetime.
+fn char_literal_end(chars: &[char], i: usize) -> Option<usize> {
+    match chars.get(i + 1)? {
+        '\\' => {
+            let close = chars[i + 2..].iter().position(|&c| c == '\'')?;
+            Some(i + 2 + close + 1)
+        }
+        _ => (chars.get(i + 2) == Some(&'\'')).then_some(i + 3),
+    }
+}
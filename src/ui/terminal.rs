@@ -0,0 +1,56 @@
+use crate::app::App;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use tui_term::widget::{Cursor, PseudoTerminal};
+
+const RUST_ORANGE: Color = Color::Rgb(183, 65, 14);
+
+/// The TERMINAL tab: an embedded shell, or instructions to start one.
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let (title, border) = if app.terminal_focused {
+        (
+            " TERMINAL [FOCUSED] ",
+            Style::default()
+                .fg(RUST_ORANGE)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        (" TERMINAL ", Style::default().fg(Color::DarkGray))
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(border);
+
+    let Some(pty) = &app.terminal else {
+        let message = match &app.terminal_status {
+            Some(status) => format!("{}\n\nPress [F2] to start a new shell.", status),
+            None => "Press [F2] to start a shell in the workspace.\n\n\
+                     Edit your mission, run rustc or cargo, then press [F2] again to return to the game."
+                .to_string(),
+        };
+        f.render_widget(
+            Paragraph::new(message)
+                .block(block)
+                .wrap(Wrap { trim: true })
+                .style(Style::default().fg(Color::DarkGray)),
+            area,
+        );
+        return;
+    };
+
+    let inner = block.inner(area);
+    pty.resize(inner.height, inner.width);
+    let parser = pty.screen();
+    let cursor = Cursor::default().visibility(app.terminal_focused);
+    f.render_widget(
+        PseudoTerminal::new(parser.screen())
+            .block(block)
+            .cursor(cursor),
+        area,
+    );
+}
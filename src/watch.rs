@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often the file's mtime is checked.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// How long the file must stay unchanged before a save counts. Editors often
+/// write a file in several steps (truncate, write, rename).
+const DEBOUNCE: Duration = Duration::from_millis(400);
+
+/// Watches a mission source file for saves by polling its modification time
+/// from the event loop.
+pub struct SourceWatch {
+    path: PathBuf,
+    last_seen: Option<SystemTime>,
+    last_poll: Instant,
+    /// When the latest not-yet-reported change was seen.
+    changed_at: Option<Instant>,
+}
+
+impl SourceWatch {
+    /// Starts watching from the file's current state; existing edits don't count.
+    pub fn new(path: PathBuf) -> Self {
+        let last_seen = modified(&path);
+        Self {
+            path,
+            last_seen,
+            last_poll: Instant::now(),
+            changed_at: None,
+        }
+    }
+
+    /// Returns `true` once per save, after the file has settled.
+    pub fn poll(&mut self) -> bool {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return false;
+        }
+        self.last_poll = Instant::now();
+
+        let current = modified(&self.path);
+        // A missing file is usually an editor mid-save; wait for it to come back.
+        if current.is_some() && current != self.last_seen {
+            self.last_seen = current;
+            self.changed_at = Some(Instant::now());
+            return false;
+        }
+        match self.changed_at {
+            Some(at) if at.elapsed() >= DEBOUNCE => {
+                self.changed_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+fn modified(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}